@@ -0,0 +1,243 @@
+use crate::{
+    error::{IoResultExt, Result},
+    fetcher::FetchResponse,
+};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::trace;
+
+// First line of every cache file
+const CACHE_MAGIC: &str = "httpdirectory-cache 1";
+
+/// On-disk cache of the listings retrieved by the HTTP client of
+/// an `HttpDirectory` (see `HttpDirectoryBuilder::disk_cache()`).
+/// Each url is stored in its own file in `dir` with the raw body,
+/// the headers and the time it has been fetched. A cached listing
+/// is served without any request while it is younger than `ttl`.
+/// In offline mode only the cache is used whatever the age of
+/// listings is, and a url that is not cached is an error.
+/// Polls of `HttpDirectory::watch()` are served from the cache
+/// too: a change is not seen before the listing expires.
+///
+/// ```rust
+///   use httpdirectory::{cache::DiskCache, httpdirectorybuilder::HttpDirectoryBuilder};
+///   use std::time::Duration;
+///   async fn cache_example() {
+///     let cache = DiskCache::new("/tmp/httpdirectory-cache").ttl(Duration::from_secs(600));
+///     if let Ok(httpdir) = HttpDirectoryBuilder::new().disk_cache(cache).build("https://cloud.debian.org/images/cloud/").await {
+///         println!("{:?}", httpdir.dirs());
+///     }
+///   }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+    offline: bool,
+}
+
+impl DiskCache {
+    /// Returns a new cache stored in `dir` (created when needed)
+    /// with a one hour time to live and offline mode disabled
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DiskCache {
+            dir: dir.into(),
+            ttl: Duration::from_secs(3600),
+            offline: false,
+        }
+    }
+
+    /// Sets the time during which a cached listing is served
+    /// without any request
+    #[must_use]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Enables or disables the offline mode: listings are then
+    /// only served from the cache, whatever their age is
+    #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Returns the directory where listings are stored
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Tells whether the offline mode is enabled
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    // File where the listing of `url` is stored
+    fn path(&self, url: &str) -> PathBuf {
+        let name = Sha256::digest(url.as_bytes()).iter().fold(String::new(), |mut name, byte| {
+            let _ = write!(name, "{byte:02x}");
+            name
+        });
+        self.dir.join(format!("{name}.cache"))
+    }
+
+    // Returns the cached response of `url` if it is fresh enough
+    // (or whatever its age is in offline mode)
+    pub(crate) async fn get(&self, url: &str) -> Option<FetchResponse> {
+        let (fetched, response) = self.read(url).await?;
+
+        let age = SystemTime::now().duration_since(fetched).unwrap_or_default();
+        if self.offline || age <= self.ttl {
+            trace!("Serving {url} from cache (age {age:?})");
+            Some(response)
+        } else {
+            trace!("Cached {url} is too old (age {age:?})");
+            None
+        }
+    }
+
+    // Returns the time the listing of `url` has been fetched and
+    // its cached response whatever its age is
+    async fn read(&self, url: &str) -> Option<(SystemTime, FetchResponse)> {
+        let path = self.path(url);
+        let content = tokio::fs::read_to_string(&path).await.ok()?;
+        let (cached_url, fetched, response) = parse_cache_file(&content)?;
+
+        if cached_url == url {
+            Some((fetched, response))
+        } else {
+            trace!("Cache file {} is not the one of {url}", path.display());
+            None
+        }
+    }
+
+    // Stores again the cached listing of `url` (if any) with the
+    // current time: the server told that it has not been modified
+    // (304 HTTP status code) so it is fresh again
+    pub(crate) async fn refresh(&self, url: &str) -> Result<()> {
+        match self.read(url).await {
+            Some((_, response)) => {
+                trace!("Refreshing cached {url}");
+                self.store(url, &response).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Stores `response`, the listing of `url`, with the current
+    // time (in milliseconds since the Unix epoch). The content is
+    // written to a temporary file of the same directory renamed
+    // over the cache file so that a reader (or a concurrent store)
+    // never sees a partially written file
+    pub(crate) async fn store(&self, url: &str, response: &FetchResponse) -> Result<()> {
+        let fetched = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let mut content = format!("{CACHE_MAGIC}\nurl: {url}\nfetched: {fetched}\n");
+        for (name, value) in &response.headers {
+            let _ = writeln!(content, "{name}: {value}");
+        }
+        content.push('\n');
+        content.push_str(&response.body);
+
+        tokio::fs::create_dir_all(&self.dir).await.with_path(&self.dir)?;
+        let path = self.path(url);
+        let temp_path = path.with_extension(format!("{:016x}.tmp", fastrand::u64(..)));
+        tokio::fs::write(&temp_path, content).await.with_path(&temp_path)?;
+        if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e).with_path(&path);
+        }
+        Ok(())
+    }
+}
+
+// Parses the content of a cache file and returns the url,
+// the time it has been fetched and the cached response
+fn parse_cache_file(content: &str) -> Option<(&str, SystemTime, FetchResponse)> {
+    let (head, body) = content.split_once("\n\n")?;
+    let mut lines = head.lines();
+
+    if lines.next()? != CACHE_MAGIC {
+        return None;
+    }
+    let url = lines.next()?.strip_prefix("url: ")?;
+    let fetched = lines.next()?.strip_prefix("fetched: ")?.parse().ok()?;
+    let headers = lines
+        .filter_map(|line| line.split_once(": "))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    let response = FetchResponse {
+        status: 200,
+        headers,
+        body: body.to_string(),
+    };
+    Some((url, UNIX_EPOCH + Duration::from_millis(fetched), response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiskCache;
+    use crate::fetcher::FetchResponse;
+    use std::time::Duration;
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    fn temp_cache(name: &str) -> DiskCache {
+        let dir = std::env::temp_dir().join(format!("httpdirectory-cache-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        DiskCache::new(dir)
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_round_trip() {
+        let cache = temp_cache("round-trip");
+        let response = FetchResponse::new(200, "<ul>\n\n<li>file</li>\n</ul>").with_header("etag", "\"v1\"");
+
+        assert_eq!(cache.get("http://example.org/").await, None);
+        cache.store("http://example.org/", &response).await.unreachable();
+        assert_eq!(cache.get("http://example.org/").await, Some(response));
+        assert_eq!(cache.get("http://example.org/other/").await, None);
+
+        // Storing again replaces the cache file and leaves no temporary file
+        let response = FetchResponse::new(200, "<ul>\n<li>other</li>\n</ul>");
+        cache.store("http://example.org/", &response).await.unreachable();
+        assert_eq!(cache.get("http://example.org/").await, Some(response));
+        assert_eq!(std::fs::read_dir(cache.dir()).unreachable().count(), 1);
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_ttl_and_offline() {
+        let cache = temp_cache("ttl").ttl(Duration::ZERO);
+        cache.store("http://example.org/", &FetchResponse::new(200, "body")).await.unreachable();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(cache.get("http://example.org/").await, None);
+        let cache = cache.offline(true);
+        assert_eq!(cache.get("http://example.org/").await.map(|response| response.body), Some("body".to_string()));
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_refresh() {
+        let cache = temp_cache("refresh").ttl(Duration::from_millis(200));
+        cache.refresh("http://example.org/").await.unreachable();
+        assert_eq!(cache.get("http://example.org/").await, None);
+
+        cache.store("http://example.org/", &FetchResponse::new(200, "body")).await.unreachable();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(cache.get("http://example.org/").await, None);
+
+        cache.refresh("http://example.org/").await.unreachable();
+        assert_eq!(cache.get("http://example.org/").await.map(|response| response.body), Some("body".to_string()));
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::{IoResultExt, Result};
+use sha2::digest::DynDigest;
+use std::{collections::HashMap, fmt, fmt::Write as _, path::Path};
+use tokio::io::AsyncReadExt;
+use tracing::trace;
+
+/// Algorithms of the checksum manifests that are understood
+/// (ordered from the weakest to the strongest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChecksumAlgorithm {
+    /// MD5 digests of `MD5SUMS` manifests
+    Md5,
+
+    /// SHA-256 digests of `SHA256SUMS` manifests
+    Sha256,
+
+    /// SHA-512 digests of `SHA512SUMS` manifests
+    Sha512,
+}
+
+impl ChecksumAlgorithm {
+    /// Returns the algorithm of a checksum manifest from its file
+    /// name (`MD5SUMS`, `SHA256SUMS` or `SHA512SUMS` with any case
+    /// and an optional `.txt` extension) or None if `name` is not
+    /// the name of a known manifest
+    #[must_use]
+    pub fn from_manifest_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        match name.strip_suffix(".TXT").unwrap_or(&name) {
+            "MD5SUMS" => Some(ChecksumAlgorithm::Md5),
+            "SHA256SUMS" => Some(ChecksumAlgorithm::Sha256),
+            "SHA512SUMS" => Some(ChecksumAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    // Length of an hexadecimal digest of this algorithm
+    fn hex_len(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Md5 => 32,
+            ChecksumAlgorithm::Sha256 => 64,
+            ChecksumAlgorithm::Sha512 => 128,
+        }
+    }
+
+    // Returns a new hasher for this algorithm
+    fn hasher(self) -> Box<dyn DynDigest + Send> {
+        match self {
+            ChecksumAlgorithm::Md5 => Box::new(md5::Md5::default()),
+            ChecksumAlgorithm::Sha256 => Box::new(sha2::Sha256::default()),
+            ChecksumAlgorithm::Sha512 => Box::new(sha2::Sha512::default()),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumAlgorithm::Md5 => write!(f, "MD5"),
+            ChecksumAlgorithm::Sha256 => write!(f, "SHA256"),
+            ChecksumAlgorithm::Sha512 => write!(f, "SHA512"),
+        }
+    }
+}
+
+/// Expected digest of a file as found in a checksum manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checksum {
+    algorithm: ChecksumAlgorithm,
+    digest: String,
+}
+
+impl Checksum {
+    /// Returns a new `Checksum` with `algorithm` and an hexadecimal
+    /// `digest` (stored in lower case)
+    #[must_use]
+    pub fn new(algorithm: ChecksumAlgorithm, digest: &str) -> Self {
+        Checksum {
+            algorithm,
+            digest: digest.to_ascii_lowercase(),
+        }
+    }
+
+    /// Returns the algorithm of this checksum
+    #[must_use]
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the expected digest in lower case hexadecimal
+    #[must_use]
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
+    /// Computes the digest of the file at `path` with the algorithm
+    /// of this checksum and returns it in lower case hexadecimal
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can not be read
+    pub async fn compute_file(&self, path: &Path) -> Result<String> {
+        let mut file = tokio::fs::File::open(path).await.with_path(path)?;
+        let mut hasher = self.algorithm.hasher();
+        let mut buffer = vec![0; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer).await.with_path(path)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(hasher.finalize().iter().fold(String::new(), |mut digest, byte| {
+            let _ = write!(digest, "{byte:02x}");
+            digest
+        }))
+    }
+
+    /// Tells whether the file at `path` matches this checksum
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can not be read
+    pub async fn verify_file(&self, path: &Path) -> Result<bool> {
+        Ok(self.compute_file(path).await? == self.digest)
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest)
+    }
+}
+
+/// Checksum manifest (`SHA256SUMS` like file) that maps file
+/// names to their expected digests. Lines produced by
+/// `sha256sum` (`digest  name` or `digest *name`) and by BSD
+/// tools (`SHA256 (name) = digest`) are understood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumManifest {
+    algorithm: ChecksumAlgorithm,
+    digests: HashMap<String, String>,
+}
+
+impl ChecksumManifest {
+    /// Parses `body`, a manifest of `algorithm` digests. Lines that
+    /// are not understood or whose digest has not the length of
+    /// `algorithm` are ignored. Leading `./` of names is removed.
+    #[must_use]
+    pub fn parse(algorithm: ChecksumAlgorithm, body: &str) -> Self {
+        let mut digests = HashMap::new();
+
+        for line in body.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match parse_line(line) {
+                Some((digest, name))
+                    if digest.len() == algorithm.hex_len() && digest.bytes().all(|b| b.is_ascii_hexdigit()) =>
+                {
+                    let name = name.strip_prefix("./").unwrap_or(name);
+                    digests.insert(name.to_string(), digest.to_ascii_lowercase());
+                }
+                _ => trace!("Ignoring checksum manifest line '{line}'"),
+            }
+        }
+
+        ChecksumManifest {
+            algorithm,
+            digests,
+        }
+    }
+
+    /// Returns the algorithm of this manifest
+    #[must_use]
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the `Checksum` of the file `name` if it is listed
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Checksum> {
+        self.digests.get(name).map(|digest| Checksum::new(self.algorithm, digest))
+    }
+
+    /// Returns the number of files listed in this manifest
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Tells whether this manifest lists no file at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+}
+
+// Splits a manifest line into its digest and file name
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    if let Some((left, digest)) = line.rsplit_once(" = ")
+        && let Some((_, name)) = left.split_once(" (")
+    {
+        // BSD style: SHA256 (name) = digest
+        return Some((digest.trim(), name.strip_suffix(')')?));
+    }
+
+    let (digest, name) = line.split_once(char::is_whitespace)?;
+    let name = name.trim_start();
+    Some((digest, name.strip_prefix('*').unwrap_or(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checksum, ChecksumAlgorithm, ChecksumManifest};
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    const SHA256_HELLO: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_from_manifest_name() {
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("SHA256SUMS"), Some(ChecksumAlgorithm::Sha256));
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("sha512sums.txt"), Some(ChecksumAlgorithm::Sha512));
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("MD5SUMS"), Some(ChecksumAlgorithm::Md5));
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("SHA256SUMS.sign"), None);
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("README"), None);
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let body = format!(
+            "# comment\n{SHA256_HELLO}  hello.txt\n{}  *./image.qcow2\nSHA256 (bsd.iso) = {}\ntooshort  short.txt\n\n",
+            SHA256_HELLO.to_uppercase(),
+            "a".repeat(64)
+        );
+        let manifest = ChecksumManifest::parse(ChecksumAlgorithm::Sha256, &body);
+
+        assert_eq!(manifest.len(), 3);
+        assert_eq!(manifest.get("hello.txt").map(|c| c.to_string()), Some(format!("SHA256:{SHA256_HELLO}")));
+        assert_eq!(manifest.get("image.qcow2").map(|c| c.digest().to_string()), Some(SHA256_HELLO.to_string()));
+        assert_eq!(manifest.get("bsd.iso").map(|c| c.digest().to_string()), Some("a".repeat(64)));
+        assert_eq!(manifest.get("short.txt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_file() {
+        let path = std::env::temp_dir().join(format!("httpdirectory-checksum-{}", std::process::id()));
+        std::fs::write(&path, "hello").unreachable();
+
+        assert_eq!(Checksum::new(ChecksumAlgorithm::Sha256, SHA256_HELLO).verify_file(&path).await.ok(), Some(true));
+        assert_eq!(
+            Checksum::new(ChecksumAlgorithm::Md5, "5d41402abc4b2a76b9719d911017c592").verify_file(&path).await.ok(),
+            Some(true)
+        );
+        assert_eq!(
+            Checksum::new(ChecksumAlgorithm::Sha512, &"0".repeat(128)).verify_file(&path).await.ok(),
+            Some(false)
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{detect::SiteType, fetcher::FetchResponse, httpdirectoryentry::HttpDirectoryEntry};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::trace;
+
+// A listing previously retrieved with its validators
+#[derive(Debug, Clone)]
+pub(crate) struct CachedListing {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    pub(crate) entries: Vec<HttpDirectoryEntry>,
+    pub(crate) site_type: Option<SiteType>,
+}
+
+// Remembers the `ETag` and `Last-Modified` validators of each url
+// retrieved with the entries parsed from it so that conditional
+// requests may be sent and a 304 HTTP status code answered by
+// reusing those entries. Only listings with a validator are kept
+// and at most `capacity` of them: the least recently used listing
+// is evicted to make room for a new one.
+#[derive(Debug)]
+pub(crate) struct ListingCache {
+    enabled: bool,
+    capacity: usize,
+    listings: Mutex<Listings>,
+}
+
+// Cached listings by url and their urls from the least to the
+// most recently used one
+#[derive(Debug, Default)]
+struct Listings {
+    by_url: HashMap<String, CachedListing>,
+    recency: VecDeque<String>,
+}
+
+impl Listings {
+    // Marks `url` as the most recently used one
+    fn touch(&mut self, url: &str) {
+        if let Some(position) = self.recency.iter().position(|cached| cached == url)
+            && let Some(url) = self.recency.remove(position)
+        {
+            self.recency.push_back(url);
+        }
+    }
+
+    fn remove(&mut self, url: &str) {
+        if self.by_url.remove(url).is_some() {
+            self.recency.retain(|cached| cached != url);
+        }
+    }
+
+    // Inserts `listing` evicting the least recently used listings
+    // so that no more than `capacity` listings are kept
+    fn insert(&mut self, url: &str, listing: CachedListing, capacity: usize) {
+        if self.by_url.insert(url.to_string(), listing).is_some() {
+            self.touch(url);
+        } else {
+            self.recency.push_back(url.to_string());
+        }
+
+        while self.by_url.len() > capacity {
+            let Some(evicted) = self.recency.pop_front() else {
+                break;
+            };
+            trace!("Evicting cached listing of {evicted}");
+            self.by_url.remove(&evicted);
+        }
+    }
+}
+
+impl Default for ListingCache {
+    // Conditional requests are enabled by default
+    fn default() -> Self {
+        ListingCache::new(true)
+    }
+}
+
+impl ListingCache {
+    // Maximum number of listings kept by default
+    pub(crate) const DEFAULT_CAPACITY: usize = 256;
+
+    pub(crate) fn new(enabled: bool) -> Self {
+        ListingCache::with_capacity(enabled, ListingCache::DEFAULT_CAPACITY)
+    }
+
+    // A cache keeping at most `capacity` listings (at least one)
+    pub(crate) fn with_capacity(enabled: bool, capacity: usize) -> Self {
+        ListingCache {
+            enabled,
+            capacity: capacity.max(1),
+            listings: Mutex::new(Listings::default()),
+        }
+    }
+
+    // Returns the cached listing of `url` if any, which becomes the
+    // most recently used one
+    pub(crate) fn get(&self, url: &str) -> Option<CachedListing> {
+        let mut listings = self.listings.lock().ok()?;
+        let listing = listings.by_url.get(url).cloned()?;
+        listings.touch(url);
+        Some(listing)
+    }
+
+    // Returns the `If-None-Match` and `If-Modified-Since` headers
+    // for `url` built from the validators of its cached listing
+    pub(crate) fn conditional_headers(&self, url: &str) -> Vec<(String, String)> {
+        let mut headers = vec![];
+        let Ok(listings) = self.listings.lock() else {
+            return headers;
+        };
+        if let Some(listing) = listings.by_url.get(url) {
+            if let Some(etag) = &listing.etag {
+                headers.push(("If-None-Match".to_string(), etag.clone()));
+            }
+            if let Some(last_modified) = &listing.last_modified {
+                headers.push(("If-Modified-Since".to_string(), last_modified.clone()));
+            }
+        }
+        headers
+    }
+
+    // Caches `entries` and `site_type` parsed from `response`, the
+    // listing of `url`, when the response has a validator
+    pub(crate) fn store(
+        &self,
+        url: &str,
+        response: &FetchResponse,
+        entries: &[HttpDirectoryEntry],
+        site_type: Option<&SiteType>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let etag = response.header("ETag").map(str::to_string);
+        let last_modified = response.header("Last-Modified").map(str::to_string);
+        let Ok(mut listings) = self.listings.lock() else {
+            return;
+        };
+
+        if etag.is_none() && last_modified.is_none() {
+            listings.remove(url);
+        } else {
+            trace!("Caching listing of {url} (ETag: {etag:?}, Last-Modified: {last_modified:?})");
+            listings.insert(
+                url,
+                CachedListing {
+                    etag,
+                    last_modified,
+                    entries: entries.to_vec(),
+                    site_type: site_type.cloned(),
+                },
+                self.capacity,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListingCache;
+    use crate::{fetcher::FetchResponse, httpdirectoryentry::HttpDirectoryEntry};
+
+    #[test]
+    fn test_listing_cache() {
+        let cache = ListingCache::default();
+        let entries = vec![HttpDirectoryEntry::new("file", "", "12", "file")];
+        let response = FetchResponse::new(200, "")
+            .with_header("ETag", "\"v1\"")
+            .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT");
+
+        assert!(cache.conditional_headers("http://example.org/").is_empty());
+        cache.store("http://example.org/", &response, &entries, None);
+        assert_eq!(
+            cache.conditional_headers("http://example.org/"),
+            vec![
+                ("If-None-Match".to_string(), "\"v1\"".to_string()),
+                ("If-Modified-Since".to_string(), "Wed, 21 Oct 2015 07:28:00 GMT".to_string())
+            ]
+        );
+        assert_eq!(cache.get("http://example.org/").map(|listing| listing.entries.len()), Some(1));
+
+        // A response without validators forgets the listing
+        cache.store("http://example.org/", &FetchResponse::new(200, ""), &entries, None);
+        assert!(cache.get("http://example.org/").is_none());
+
+        let disabled = ListingCache::new(false);
+        disabled.store("http://example.org/", &response, &entries, None);
+        assert!(disabled.conditional_headers("http://example.org/").is_empty());
+    }
+
+    #[test]
+    fn test_listing_cache_evicts_least_recently_used() {
+        let cache = ListingCache::with_capacity(true, 2);
+        let entries = vec![HttpDirectoryEntry::new("file", "", "12", "file")];
+        let response = FetchResponse::new(200, "").with_header("ETag", "\"v1\"");
+
+        cache.store("http://example.org/a/", &response, &entries, None);
+        cache.store("http://example.org/b/", &response, &entries, None);
+        // a/ is now more recently used than b/
+        assert!(cache.get("http://example.org/a/").is_some());
+        cache.store("http://example.org/c/", &response, &entries, None);
+
+        assert!(cache.get("http://example.org/a/").is_some());
+        assert!(cache.get("http://example.org/b/").is_none());
+        assert!(cache.get("http://example.org/c/").is_some());
+
+        // Storing again a cached url does not evict anything
+        cache.store("http://example.org/a/", &response, &entries, None);
+        assert!(cache.get("http://example.org/c/").is_some());
+        assert!(cache.get("http://example.org/a/").is_some());
+    }
+}
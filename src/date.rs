@@ -0,0 +1,225 @@
+use crate::entry::try_parse_date;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use tracing::trace;
+
+// Local date and the offset printed with it if any
+pub(crate) type ParsedDate = (NaiveDateTime, Option<FixedOffset>);
+
+// English abbreviations of months understood by `%b` and `%B`
+const ENGLISH_MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Month names of a language used to read dates printed by non
+/// English listings (`05-Avr-2024 11:59` or `05-Mär-2024 11:59`).
+/// A localized month name found in a date is replaced by its English
+/// abbreviation before trying the date formats. Names are compared
+/// without any case and a dot following an abbreviation is ignored.
+///
+/// ```rust
+///   use httpdirectory::date::MonthNames;
+///   let dutch = MonthNames::new([
+///       &["jan", "januari"], &["feb", "februari"], &["mrt", "maart"], &["apr", "april"],
+///       &["mei"], &["jun", "juni"], &["jul", "juli"], &["aug", "augustus"],
+///       &["sep", "september"], &["okt", "oktober"], &["nov", "november"], &["dec", "december"],
+///   ]);
+///   assert_ne!(dutch, MonthNames::german());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthNames {
+    months: Vec<Vec<String>>,
+}
+
+impl MonthNames {
+    /// Returns the month names of a language: for each month from
+    /// January to December the full and/or abbreviated names that
+    /// listings may print
+    #[must_use]
+    pub fn new(months: [&[&str]; 12]) -> Self {
+        MonthNames {
+            months: months
+                .iter()
+                .map(|names| names.iter().map(|name| name.trim_end_matches('.').to_lowercase()).collect())
+                .collect(),
+        }
+    }
+
+    /// French month names (`janv.`, `févr.`, `avr`, `juil`, `août`, ...)
+    #[must_use]
+    pub fn french() -> Self {
+        MonthNames::new([
+            &["jan", "janv", "janvier"],
+            &["fév", "fev", "févr", "fevr", "février", "fevrier"],
+            &["mar", "mars"],
+            &["avr", "avril"],
+            &["mai"],
+            &["juin"],
+            &["juil", "juillet"],
+            &["aoû", "aou", "août", "aout"],
+            &["sep", "sept", "septembre"],
+            &["oct", "octobre"],
+            &["nov", "novembre"],
+            &["déc", "dec", "décembre", "decembre"],
+        ])
+    }
+
+    /// German month names (`Mär`, `Mai`, `Okt`, `Dez`, ...)
+    #[must_use]
+    pub fn german() -> Self {
+        MonthNames::new([
+            &["jan", "jän", "januar", "jänner"],
+            &["feb", "februar"],
+            &["mär", "mrz", "märz"],
+            &["apr", "april"],
+            &["mai"],
+            &["jun", "juni"],
+            &["jul", "juli"],
+            &["aug", "august"],
+            &["sep", "sept", "september"],
+            &["okt", "oktober"],
+            &["nov", "november"],
+            &["dez", "dezember"],
+        ])
+    }
+
+    /// Spanish month names (`ene`, `abr`, `ago`, `dic`, ...)
+    #[must_use]
+    pub fn spanish() -> Self {
+        MonthNames::new([
+            &["ene", "enero"],
+            &["feb", "febrero"],
+            &["mar", "marzo"],
+            &["abr", "abril"],
+            &["may", "mayo"],
+            &["jun", "junio"],
+            &["jul", "julio"],
+            &["ago", "agosto"],
+            &["sep", "sept", "septiembre"],
+            &["oct", "octubre"],
+            &["nov", "noviembre"],
+            &["dic", "diciembre"],
+        ])
+    }
+
+    // Returns the month (0 for January) named `word` if any
+    fn month(&self, word: &str) -> Option<usize> {
+        let word = word.to_lowercase();
+        self.months.iter().position(|names| names.contains(&word))
+    }
+
+    // Replaces the first localized month name found in `date` by
+    // its English abbreviation. Returns None when `date` does not
+    // contain any month name of this language.
+    fn translate(&self, date: &str) -> Option<String> {
+        let mut start = None;
+
+        for (index, c) in date.char_indices().chain([(date.len(), ' ')]) {
+            match (start, c.is_alphabetic()) {
+                (None, true) => start = Some(index),
+                (Some(begin), false) => {
+                    if let Some(month) = self.month(&date[begin..index]) {
+                        let end = if c == '.' {
+                            index + 1
+                        } else {
+                            index
+                        };
+                        let translated = format!("{}{}{}", &date[..begin], ENGLISH_MONTHS[month], &date[end..]);
+                        trace!("Translated date '{date}' into '{translated}'");
+                        return Some(translated);
+                    }
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+// Parses `date` with a user `format` that may contain an offset
+fn parse_with_format(date: &str, format: &str) -> Option<ParsedDate> {
+    if let Ok(d) = DateTime::parse_from_str(date, format) {
+        return Some((d.naive_local(), Some(*d.offset())));
+    }
+    match NaiveDateTime::parse_from_str(date, format) {
+        Ok(d) => Some((d, None)),
+        Err(e) => {
+            trace!("Error while parsing date ({date}) with user format '{format}': {e}");
+            None
+        }
+    }
+}
+
+// Settings used to parse the dates that the built-in formats do
+// not understand (user formats and month names) and to give a
+// timezone to local dates. Held by `ParseOptions`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DateParser {
+    formats: Vec<String>,
+    month_names: Vec<MonthNames>,
+    timezone: Option<FixedOffset>,
+}
+
+impl DateParser {
+    pub(crate) fn add_format(&mut self, format: &str) {
+        self.formats.push(format.to_string());
+    }
+
+    pub(crate) fn add_month_names(&mut self, month_names: MonthNames) {
+        self.month_names.push(month_names);
+    }
+
+    pub(crate) fn set_timezone(&mut self, timezone: FixedOffset) {
+        self.timezone = Some(timezone);
+    }
+
+    pub(crate) fn timezone(&self) -> Option<FixedOffset> {
+        self.timezone
+    }
+
+    // Tries the user formats on `date` and then, for each language
+    // of month names, the built-in and user formats on `date`
+    // translated into English
+    pub(crate) fn parse(&self, date: &str) -> Option<ParsedDate> {
+        if date.is_empty() {
+            return None;
+        }
+
+        if let Some(parsed) = self.formats.iter().find_map(|format| parse_with_format(date, format)) {
+            return Some(parsed);
+        }
+
+        self.month_names.iter().filter_map(|month_names| month_names.translate(date)).find_map(|translated| {
+            try_parse_date(&translated)
+                .or_else(|| self.formats.iter().find_map(|format| parse_with_format(&translated, format)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DateParser, MonthNames};
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(MonthNames::french().translate("05-Avr-2024 11:59"), Some("05-Apr-2024 11:59".to_string()));
+        assert_eq!(MonthNames::french().translate("5 févr. 2024 11:59"), Some("5 Feb 2024 11:59".to_string()));
+        assert_eq!(MonthNames::german().translate("05-Mär-2024 11:59"), Some("05-Mar-2024 11:59".to_string()));
+        assert_eq!(MonthNames::german().translate("05-Apr-2024 11:59"), Some("05-Apr-2024 11:59".to_string()));
+        assert_eq!(MonthNames::german().translate("2024-04-05 11:59"), None);
+    }
+
+    #[test]
+    fn test_date_parser() {
+        let mut parser = DateParser::default();
+        assert_eq!(parser.parse("05-Avr-2024 11:59"), None);
+
+        parser.add_month_names(MonthNames::french());
+        parser.add_format("%d.%m.%Y %Hh%M");
+        parser.add_format("%e %b %Y, %H:%M");
+
+        let date = |date: &str| parser.parse(date).map(|(d, _)| d.format("%Y-%m-%d %H:%M").to_string());
+        assert_eq!(date("05-Avr-2024 11:59"), Some("2024-04-05 11:59".to_string()));
+        assert_eq!(date("05.04.2024 11h59"), Some("2024-04-05 11:59".to_string()));
+        assert_eq!(date(" 5 juil. 2024, 11:59"), Some("2024-07-05 11:59".to_string()));
+        assert_eq!(date("05-Foo-2024 11:59"), None);
+    }
+}
@@ -1,26 +1,61 @@
+use crate::scrapers::Scraper;
 use regex::Regex;
+use std::fmt;
 use std::sync::LazyLock;
 use unwrap_unreachable::UnwrapUnreachable;
 
-/// Site type enumeration.
-#[derive(Debug, PartialEq, Eq)]
-pub enum SiteType {
-    NotNamed(PureHtml),
-    H5ai(String),      // from https://github.com/lrsjng/h5ai
-    Snt,               // SNT index generator from https://snt.utwente.nl/en/
-    MiniServe(String), // Miniserv file server from https://crates.io/crates/miniserve
-    Stil,              // Stil STatic Index Listing from https://crates.io/crates/stil
-    None,
+/// Listing format detected when scraping a directory: the name
+/// of the scraper that has been used and the version of the
+/// listing software when it is known (h5ai, miniserve)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SiteType {
+    name: String,
+    version: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum PureHtml {
-    Table,
-    Pre,
-    Ul,
+impl SiteType {
+    /// Returns a new `SiteType` named `name` with an optional `version`
+    #[must_use]
+    pub fn new(name: &str, version: Option<&str>) -> Self {
+        SiteType {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    // Returns the `SiteType` of `body` detected by `scraper`
+    pub(crate) fn from_scraper(scraper: &dyn Scraper, body: &str) -> Self {
+        SiteType {
+            name: scraper.name().to_string(),
+            version: scraper.version(body),
+        }
+    }
+
+    /// Name of the scraper that understood the listing
+    /// ("h5ai", "miniserve", "table", ...)
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Version of the listing software if known
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+impl fmt::Display for SiteType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{} {version}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
 }
 
-// Regex used to determine `SiteType`
+// Regexes used to detect listing formats
 static H5AI_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"powered by h5ai (v\d+.\d+.\d+)").unreachable());
 
 // Some websites are using "Modified" instead of "Last modified"
@@ -31,6 +66,14 @@ static TABLE_RE: LazyLock<Regex> =
 static TABLE_DATE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?msi)<table(.+?<th.+?Date.+?</th.+?)</table").unreachable());
 
+static LIGHTTPD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<div class="foot">lighttpd/(\d+.\d+.\d+)"#).unreachable());
+
+// IIS lines end with a 12-hour clock time followed by the
+// size or the `<dir>` marker (escaped in HTML) and the link
+static IIS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\d{1,2}:\d{2}\s+[AP]M\s+(&lt;dir&gt;|\d+)\s+<a\s+href=").unreachable());
+
 static MINISERVE_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
         r#"<div class="version"><a href="https://github.com/svenstaro/miniserve">miniserve</a>/(\d+.\d+.\d+)</div>"#,
@@ -42,7 +85,7 @@ static MINISERVE_RE: LazyLock<Regex> = LazyLock::new(|| {
 // we can match a column name "Modified",
 // "Last Modified" or "Date" within the table
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn detect_table(body: &str) -> bool {
+pub(crate) fn detect_table(body: &str) -> bool {
     if TABLE_RE.is_match(body) {
         true
     } else {
@@ -51,7 +94,7 @@ fn detect_table(body: &str) -> bool {
 }
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn detect_h5ai(body: &str) -> Option<String> {
+pub(crate) fn detect_h5ai(body: &str) -> Option<String> {
     if body.contains("powered by h5ai") {
         H5AI_RE.captures(body).map(|value| value[1].to_string())
     } else {
@@ -60,12 +103,12 @@ fn detect_h5ai(body: &str) -> Option<String> {
 }
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn detect_snt(body: &str) -> bool {
+pub(crate) fn detect_snt(body: &str) -> bool {
     body.contains("SNT index generator")
 }
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn detect_miniserve(body: &str) -> Option<String> {
+pub(crate) fn detect_miniserve(body: &str) -> Option<String> {
     if body.contains(r#"<div class="version"><a href="https://github.com/svenstaro/miniserve">miniserve</a>/"#) {
         MINISERVE_RE.captures(body).map(|value| value[1].to_string())
     } else {
@@ -74,43 +117,50 @@ fn detect_miniserve(body: &str) -> Option<String> {
 }
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn detect_stil(body: &str) -> bool {
+pub(crate) fn detect_stil(body: &str) -> bool {
     body.contains("<title>stil</title>")
 }
 
-impl SiteType {
-    /// Detects the possible type of the site we are
-    /// scraping information from by "analyzing" it's
-    /// body.
-    #[cfg_attr(feature = "hotpath", hotpath::measure)]
-    pub fn detect(body: &str) -> Self {
-        if let Some(version) = detect_h5ai(body) {
-            SiteType::H5ai(version)
-        } else if detect_snt(body) {
-            SiteType::Snt
-        } else if let Some(version) = detect_miniserve(body) {
-            SiteType::MiniServe(version)
-        } else if detect_stil(body) {
-            SiteType::Stil
-        } else if detect_table(body) {
-            SiteType::NotNamed(PureHtml::Table)
-        } else if body.contains("<pre>") {
-            SiteType::NotNamed(PureHtml::Pre)
-        } else if body.contains("<ul>") {
-            SiteType::NotNamed(PureHtml::Ul)
-        } else {
-            SiteType::None
-        }
-    }
+// lighttpd's mod_dirlisting names the columns of its
+// table with classes (n, m, s and t for the type)
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn detect_lighttpd(body: &str) -> bool {
+    body.contains(r#"<th class="n">"#) && body.contains(r#"<th class="m">"#) && body.contains(r#"<th class="t">"#)
+}
+
+// The version is only known when the footer gives it
+// (`server.tag` may hide it)
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn detect_lighttpd_version(body: &str) -> Option<String> {
+    LIGHTTPD_RE.captures(body).map(|value| value[1].to_string())
+}
+
+// Caddy's browse template ends with "Served with Caddy"
+// linked to its website
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn detect_caddy(body: &str) -> bool {
+    body.contains("Served with") && body.contains(r#"href="https://caddyserver.com">Caddy</a>"#)
+}
+
+// IIS directory browsing lists entries in a `<pre>` block
+// whose lines are separated by `<br>` tags
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn detect_iis(body: &str) -> bool {
+    body.to_lowercase().contains("<pre>") && IIS_RE.is_match(body)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::detect::{PureHtml, SiteType};
+    use crate::detect::SiteType;
+    use crate::scrapers::{Scraper, ScraperRegistry};
+
+    fn detected(body: &str) -> Option<&str> {
+        ScraperRegistry::builtin().detect(body).map(Scraper::name)
+    }
 
     #[test]
     fn test_empty_body() {
-        assert_eq!(SiteType::detect(""), SiteType::None);
+        assert_eq!(detected(""), None);
     }
 
     #[test]
@@ -124,7 +174,7 @@ mod tests {
               </table>
             "#;
 
-        assert_eq!(SiteType::detect(body), SiteType::NotNamed(PureHtml::Table));
+        assert_eq!(detected(body), Some("table"));
     }
 
     #[test]
@@ -155,6 +205,33 @@ mod tests {
             </pre><hr>
             "##;
 
-        assert_eq!(SiteType::detect(body), SiteType::NotNamed(PureHtml::Pre));
+        assert_eq!(detected(body), Some("pre"));
+    }
+
+    #[test]
+    fn test_body_with_iis_pre() {
+        let body = r#"<html><head><title>host - /pub/</title></head><body><H1>host - /pub/</H1><hr>
+
+<pre><A HREF="/">[To Parent Directory]</A><br><br> 5/31/2025  1:54 PM        &lt;dir&gt; <A HREF="/pub/debian/">debian</A><br> 5/31/2025  1:54 PM        12345 <A HREF="/pub/README.txt">README.txt</A><br></pre><hr></body></html>"#;
+
+        assert_eq!(detected(body), Some("iis"));
+    }
+
+    #[test]
+    fn test_site_type_with_version() {
+        let body = "<title>index - powered by h5ai v0.29.2 (https://larsjung.de/h5ai/)</title>";
+        let site_type = ScraperRegistry::builtin().detect(body).map(|scraper| SiteType::from_scraper(scraper, body));
+
+        assert_eq!(site_type, Some(SiteType::new("h5ai", Some("v0.29.2"))));
+        assert_eq!(site_type.map(|site_type| site_type.to_string()), Some("h5ai v0.29.2".to_string()));
+    }
+
+    #[test]
+    fn test_site_type_without_version() {
+        let site_type = SiteType::new("pre", None);
+
+        assert_eq!(site_type.name(), "pre");
+        assert_eq!(site_type.version(), None);
+        assert_eq!(site_type.to_string(), "pre");
     }
 }
@@ -0,0 +1,163 @@
+use crate::{entry::Entry, httpdirectoryentry::HttpDirectoryEntry};
+use std::collections::HashMap;
+use std::fmt;
+
+/// An entry present in both listings whose date and/or
+/// size changed
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModifiedEntry {
+    /// The entry as found in the old listing
+    pub old: HttpDirectoryEntry,
+
+    /// The entry as found in the new listing
+    pub new: HttpDirectoryEntry,
+
+    /// The date of the entry changed
+    pub date_changed: bool,
+
+    /// The size of the entry changed
+    pub size_changed: bool,
+}
+
+/// Differences between two listings returned by `HttpDirectory::diff()`.
+/// Entries are matched by name (parent directories are ignored). An
+/// entry that turned from a file into a directory (or the reverse) is
+/// both removed and added.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListingDiff {
+    /// Entries only found in the new listing (in the new listing's order)
+    pub added: Vec<HttpDirectoryEntry>,
+
+    /// Entries only found in the old listing (in the old listing's order)
+    pub removed: Vec<HttpDirectoryEntry>,
+
+    /// Entries found in both listings with a different date
+    /// and/or size (in the new listing's order)
+    pub modified: Vec<ModifiedEntry>,
+}
+
+impl ListingDiff {
+    /// Tells whether both listings are the same
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl fmt::Display for ListingDiff {
+    /// One line per difference: `+` for added entries, `-` for
+    /// removed ones and `~` for modified ones
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "+ {entry}")?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {entry}")?;
+        }
+        for modified in &self.modified {
+            writeln!(f, "~ {}", modified.new)?;
+        }
+        Ok(())
+    }
+}
+
+// Key used to match entries of both listings: files and
+// directories with the same name are different entries
+fn key(entry: &HttpDirectoryEntry) -> Option<(bool, &str)> {
+    match entry {
+        HttpDirectoryEntry::ParentDirectory(_) => None,
+        HttpDirectoryEntry::Directory(dir) => Some((true, dir.name())),
+        HttpDirectoryEntry::File(file) => Some((false, file.name())),
+    }
+}
+
+// Returns the `Entry` of a file or a directory
+fn inner(entry: &HttpDirectoryEntry) -> Option<&Entry> {
+    match entry {
+        HttpDirectoryEntry::ParentDirectory(_) => None,
+        HttpDirectoryEntry::Directory(entry) | HttpDirectoryEntry::File(entry) => Some(entry),
+    }
+}
+
+// Computes the differences between `old` and `new` entries
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn diff(old: &[HttpDirectoryEntry], new: &[HttpDirectoryEntry]) -> ListingDiff {
+    let old_by_key: HashMap<_, _> = old.iter().filter_map(|entry| Some((key(entry)?, entry))).collect();
+    let new_by_key: HashMap<_, _> = new.iter().filter_map(|entry| Some((key(entry)?, entry))).collect();
+    let mut listing_diff = ListingDiff::default();
+
+    for entry in new {
+        let Some(new_key) = key(entry) else {
+            continue;
+        };
+        match old_by_key.get(&new_key) {
+            Some(old_entry) => {
+                if let (Some(old_inner), Some(new_inner)) = (inner(old_entry), inner(entry)) {
+                    let date_changed = old_inner.date() != new_inner.date();
+                    let size_changed = old_inner.size() != new_inner.size();
+                    if date_changed || size_changed {
+                        listing_diff.modified.push(ModifiedEntry {
+                            old: (*old_entry).clone(),
+                            new: entry.clone(),
+                            date_changed,
+                            size_changed,
+                        });
+                    }
+                }
+            }
+            None => listing_diff.added.push(entry.clone()),
+        }
+    }
+
+    listing_diff.removed = old
+        .iter()
+        .filter(|entry| key(entry).is_some_and(|old_key| !new_by_key.contains_key(&old_key)))
+        .cloned()
+        .collect();
+
+    listing_diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff;
+    use crate::httpdirectoryentry::HttpDirectoryEntry;
+
+    fn names(entries: &[HttpDirectoryEntry]) -> Vec<&str> {
+        entries.iter().filter_map(HttpDirectoryEntry::name).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![
+            HttpDirectoryEntry::new("Parent Directory", "", "-", ".."),
+            HttpDirectoryEntry::new("same.iso", "2025-05-20 20:19", "1.0M", "same.iso"),
+            HttpDirectoryEntry::new("removed.iso", "2025-05-20 20:19", "1.0M", "removed.iso"),
+            HttpDirectoryEntry::new("newer.iso", "2025-05-20 20:19", "1.0M", "newer.iso"),
+            HttpDirectoryEntry::new("bigger.iso", "2025-05-20 20:19", "1.0M", "bigger.iso"),
+            HttpDirectoryEntry::new("kind", "2025-05-20 20:19", "1.0M", "kind"),
+        ];
+        let new = vec![
+            HttpDirectoryEntry::new("added.iso", "2025-05-21 20:19", "2.0M", "added.iso"),
+            HttpDirectoryEntry::new("same.iso", "2025-05-20 20:19", "1.0M", "same.iso"),
+            HttpDirectoryEntry::new("newer.iso", "2025-05-21 08:00", "1.0M", "newer.iso"),
+            HttpDirectoryEntry::new("bigger.iso", "2025-05-20 20:19", "1.5M", "bigger.iso"),
+            HttpDirectoryEntry::new("kind", "2025-05-20 20:19", "-", "kind/"),
+        ];
+
+        let listing_diff = diff(&old, &new);
+
+        assert_eq!(names(&listing_diff.added), vec!["added.iso", "kind"]);
+        assert_eq!(names(&listing_diff.removed), vec!["removed.iso", "kind"]);
+        let modified: Vec<_> = listing_diff
+            .modified
+            .iter()
+            .map(|m| (m.new.name().unwrap_or_default(), m.date_changed, m.size_changed))
+            .collect();
+        assert_eq!(modified, vec![("newer.iso", true, false), ("bigger.iso", false, true)]);
+        assert!(!listing_diff.is_empty());
+        assert!(diff(&old, &old).is_empty());
+    }
+}
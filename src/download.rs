@@ -0,0 +1,274 @@
+use crate::{
+    error::{HttpDirError, IoResultExt, Result},
+    fetcher::{FetchStream, Fetcher, status_error},
+    httpdirectoryentry::HttpDirectoryEntry,
+};
+use futures::StreamExt;
+use std::path::Path;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+use tracing::{debug, error};
+
+/// Progress of a download given to the callback of
+/// `HttpDirectory::download_with_progress()` after each
+/// chunk written to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of bytes of the file that are on disk (including
+    /// the bytes of a partial file that has been resumed)
+    pub downloaded: u64,
+
+    /// Total size of the file if the server told it
+    pub total: Option<u64>,
+}
+
+// Returns the total length of the file from a `Content-Range`
+// header ("bytes 100-199/200" or "bytes */200")
+fn content_range_total(response: &FetchStream) -> Option<u64> {
+    response.header("Content-Range")?.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+// Returns the first byte of the part of the file sent with a
+// `Content-Range` header ("bytes 100-199/200")
+fn content_range_start(response: &FetchStream) -> Option<u64> {
+    let range = response.header("Content-Range")?.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('/')?.0.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+// Returns the total length of the file from the `Content-Length`
+// header of a response that starts at byte `offset`
+fn content_length_total(response: &FetchStream, offset: u64) -> Option<u64> {
+    response.header("Content-Length")?.trim().parse::<u64>().ok().map(|length| length + offset)
+}
+
+// Downloads the file `entry` to `path` with `fetcher`. A partial
+// file already at `path` is resumed with a `Range` request and is
+// restarted from scratch if the server does not support ranges.
+// `progress` is called after each chunk written to disk.
+pub(crate) async fn download<F>(
+    fetcher: &dyn Fetcher,
+    entry: &HttpDirectoryEntry,
+    path: &Path,
+    mut progress: F,
+) -> Result<u64>
+where
+    F: FnMut(Progress),
+{
+    let (HttpDirectoryEntry::File(file), Some(url)) = (entry, entry.url()) else {
+        return Err(HttpDirError::NotDownloadable {
+            name: entry.name().unwrap_or("..").to_string(),
+        });
+    };
+    let expected = file.exact_size();
+
+    let offset = match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => 0,
+    };
+    debug!("Downloading {url} to '{}' from byte {offset}", path.display());
+
+    let mut response = fetcher.fetch_stream(url.as_str(), offset).await?;
+    let mut downloaded = offset;
+    let total;
+
+    match response.status {
+        206 if offset > 0 => {
+            if content_range_start(&response) != Some(offset) {
+                let content_range = response.header("Content-Range").unwrap_or_default().to_string();
+                error!("Range of {url} does not start at byte {offset}: '{content_range}'");
+                return Err(HttpDirError::UnexpectedRange {
+                    url: url.to_string(),
+                    offset,
+                    content_range,
+                });
+            }
+            total = content_range_total(&response);
+            let mut output = OpenOptions::new().append(true).open(path).await.with_path(path)?;
+            downloaded = write_body(&mut response, &mut output, path, downloaded, total, &mut progress).await?;
+        }
+        200 => {
+            total = content_length_total(&response, 0);
+            let mut output = File::create(path).await.with_path(path)?;
+            downloaded = write_body(&mut response, &mut output, path, 0, total, &mut progress).await?;
+        }
+        416 if offset > 0 => {
+            // The partial file is already complete
+            total = content_range_total(&response);
+            progress(Progress {
+                downloaded,
+                total,
+            });
+        }
+        status => {
+            error!("Error while downloading url {url}: {status}");
+            return Err(status_error(url.as_str(), status));
+        }
+    }
+
+    match expected.or(total) {
+        Some(expected) if expected != downloaded => Err(HttpDirError::SizeMismatch {
+            path: path.display().to_string(),
+            expected,
+            actual: downloaded,
+        }),
+        _ => Ok(downloaded),
+    }
+}
+
+// Writes the body of `response` at the end of `output` and
+// returns the number of bytes of the file
+async fn write_body<F>(
+    response: &mut FetchStream,
+    output: &mut File,
+    path: &Path,
+    mut downloaded: u64,
+    total: Option<u64>,
+    progress: &mut F,
+) -> Result<u64>
+where
+    F: FnMut(Progress),
+{
+    while let Some(chunk) = response.body.next().await {
+        let chunk = chunk?;
+        output.write_all(&chunk).await.with_path(path)?;
+        downloaded += chunk.len() as u64;
+        progress(Progress {
+            downloaded,
+            total,
+        });
+    }
+    output.flush().await.with_path(path)?;
+
+    Ok(downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, download};
+    use crate::{
+        fetcher::{FetchResponse, InMemoryFetcher},
+        httpdirectoryentry::HttpDirectoryEntry,
+    };
+    use std::path::PathBuf;
+    use unwrap_unreachable::UnwrapUnreachable;
+    use url::Url;
+
+    const BODY: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    fn file_entry(size: &str) -> HttpDirectoryEntry {
+        let mut entry = HttpDirectoryEntry::new("file.txt", "2025-05-20 20:19", size, "file.txt");
+        entry.resolve_url(&Url::parse("http://example.org/").unreachable());
+        entry
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("httpdirectory-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn test_download() {
+        let fetcher = InMemoryFetcher::new().with_body("http://example.org/file.txt", BODY);
+        let path = temp_path("download");
+        let mut calls = vec![];
+
+        let downloaded = download(&fetcher, &file_entry("36"), &path, |progress| calls.push(progress)).await;
+
+        assert_eq!(downloaded.ok(), Some(36));
+        assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some(BODY));
+        assert_eq!(
+            calls.last(),
+            Some(&Progress {
+                downloaded: 36,
+                total: None
+            })
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_download_resume() {
+        let fetcher = InMemoryFetcher::new().with_body("http://example.org/file.txt", BODY);
+        let path = temp_path("resume");
+        std::fs::write(&path, &BODY[..10]).unreachable();
+        let mut calls = vec![];
+
+        let downloaded = download(&fetcher, &file_entry("36"), &path, |progress| calls.push(progress)).await;
+
+        assert_eq!(downloaded.ok(), Some(36));
+        assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some(BODY));
+        assert_eq!(
+            calls,
+            vec![Progress {
+                downloaded: 36,
+                total: Some(36)
+            }]
+        );
+
+        // Already complete: the server answers 416
+        let downloaded = download(&fetcher, &file_entry("36"), &path, |_| ()).await;
+        assert_eq!(downloaded.ok(), Some(36));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_download_resume_unexpected_range() {
+        let response = FetchResponse::new(206, BODY).with_header("Content-Range", "bytes 0-35/36");
+        let fetcher = InMemoryFetcher::new().with_response("http://example.org/file.txt", response);
+        let path = temp_path("unexpected-range");
+        std::fs::write(&path, &BODY[..10]).unreachable();
+
+        match download(&fetcher, &file_entry("36"), &path, |_| ()).await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "'http://example.org/file.txt' sent range 'bytes 0-35/36' instead of the bytes from 10"
+            ),
+        }
+        // The partial file is left untouched
+        assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some(&BODY[..10]));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_download_size_mismatch() {
+        let fetcher = InMemoryFetcher::new().with_body("http://example.org/file.txt", BODY);
+        let path = temp_path("mismatch");
+
+        match download(&fetcher, &file_entry("40"), &path, |_| ()).await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                format!("Downloaded file '{}' is 36 bytes long instead of 40 bytes", path.display())
+            ),
+        }
+
+        // An approximate size is not verified
+        assert_eq!(download(&fetcher, &file_entry("1.0K"), &path, |_| ()).await.ok(), Some(36));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_download_not_a_file() {
+        let fetcher = InMemoryFetcher::new();
+        let path = temp_path("not-a-file");
+        let mut dir = HttpDirectoryEntry::new("dir/", "2025-05-20 20:19", "-", "dir/");
+        dir.resolve_url(&Url::parse("http://example.org/").unreachable());
+
+        match download(&fetcher, &dir, &path, |_| ()).await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(e.to_string(), "'dir/' is not a file with an url that could be downloaded"),
+        }
+
+        match download(&fetcher, &file_entry("36"), &path, |_| ()).await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => {
+                assert_eq!(e.to_string(), "Error retrieving content from 'http://example.org/file.txt': 404 Not Found");
+            }
+        }
+    }
+}
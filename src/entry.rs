@@ -1,12 +1,18 @@
-use chrono::NaiveDateTime;
+use crate::{
+    checksum::Checksum,
+    date::{DateParser, ParsedDate},
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use regex::Regex;
 use std::sync::LazyLock;
-use std::{cmp::Ordering, fmt};
+use std::{cmp::Ordering, collections::BTreeMap, fmt, num::IntErrorKind, ops::RangeInclusive};
 use tracing::{error, trace};
 use unwrap_unreachable::UnwrapUnreachable;
+use url::Url;
 
 /// Defines an Entry for a file or a directory
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
     /// Name of file or directory
     name: String,
@@ -15,89 +21,218 @@ pub struct Entry {
     link: String,
 
     /// Date of that file or directory
-    date: Option<NaiveDateTime>,
+    date: Option<DateTime<FixedOffset>>,
+
+    /// The listing printed the offset of the date. Otherwise the
+    /// date is a local time in the default timezone of the directory
+    date_has_offset: bool,
+
+    /// Apparent date as reported by the HTTP page
+    apparent_date: String,
 
     /// Apparent size as reported by the HTTP page used for printing
     apparent_size: String,
 
+    /// Unit system used to read the ambiguous units of the apparent size
+    unit_system: UnitSystem,
+
     /// Computed size used for sorting
-    size: usize,
+    size: u64,
+
+    /// Description of that file or directory as reported by the
+    /// HTTP page (Apache's "Description" column)
+    description: Option<String>,
+
+    /// Other columns of the listing (type, owner, ...) with their
+    /// header as printed by the HTTP page
+    extra_columns: BTreeMap<String, String>,
+
+    /// Absolute url of that file or directory resolved from
+    /// the link and the url of the listing when scraped
+    url: Option<Url>,
+
+    /// Expected digest of that file when it is listed in a
+    /// checksum manifest of the directory
+    checksum: Option<Checksum>,
 }
 
 // Direct capture of the size as a number and the unit (modifier)
-// using capture groups. There are 5 capture groups in that regex.
-// It does not capture the unit multiplier type if any (ie: i) for
-// now. See `capture_size_and_unit()` method.
-static SIZE_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?i)(\d*\.?\d*)\s*([kmgtp])i?b|(\d*\.?\d*)\s*([kmgtp]|b)|(\d*\.?\d*)").unreachable());
-
-// Tries to parse a string that should contain a date
-// with an array of known formats
-fn try_parse_date(date: &str) -> Option<NaiveDateTime> {
-    if date.len() > 3 && date.contains(':') {
-        // Format to try to parse dates
-        let parse_format = [
-            "%Y-%m-%d %H:%M",       // 2023-12-03 17:33
-            "%d-%b-%Y %H:%M",       // 05-Apr-2024 11:59
-            "%Y-%b-%d %H:%M",       // 2021-May-25 20:15
-            "%Y-%m-%d %H:%M:%S",    // 2023-12-03 17:33:19
-            "%d-%b-%Y %H:%M:%S",    // 05-Apr-2024 11:59:30
-            "%Y-%b-%d %H:%M:%S",    // 2021-May-25 20:15:46
-            "%Y/%m/%d %H:%M:%S",    // 2025/10/21 21:53:58
-            "%m/%d/%Y %r %:z",      // 05/31/2025 01:54:45 PM +00:00
-            "%Y-%m-%dT%H:%MZ",      // 2025-10-20T14:17Z
-            "%d-%m-%Y | %H:%M",     // 20-10-2025 | 13:52
-            "%Y-%m-%d %H:%M %Z",    // 2025-10-20 16:17 CEST
-            "%Y-%m-%d %H:%M:%S %Z", // 2025-09-06 18:15:23 CST
-            "%B %d, %Y %H:%M",      // October 21, 2025 20:53
-            "%d %b %Y %H:%M:%S %z", // 06 Sep 2025 10:15:23 +0000
-            "%d-%m-%Y %H:%M",       // 21-10-2025 14:19
-        ];
-
-        for pf in parse_format {
-            match NaiveDateTime::parse_from_str(date, pf) {
-                Ok(d) => {
-                    trace!("Successfully parsed date ({date}) with format '{pf}'");
-                    return Some(d);
-                }
-                Err(e) => trace!("Error while parsing date ({date}) with format '{pf}': {e}"),
+// using capture groups. There are 6 capture groups in that regex.
+// The third one captures the binary unit type if any (ie: i).
+// See `capture_size_and_unit()` method.
+static SIZE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\d*\.?\d*)\s*([kmgtp])(i)?b|(\d*\.?\d*)\s*([kmgtp]|b)|(\d*\.?\d*)").unreachable()
+});
+
+// Formats of dates printed with their offset
+const OFFSET_FORMATS: [&str; 4] = [
+    "%m/%d/%Y %r %:z",          // 05/31/2025 01:54:45 PM +00:00
+    "%d %b %Y %H:%M:%S %z",     // 06 Sep 2025 10:15:23 +0000
+    "%a, %d %b %Y %H:%M:%S %z", // Wed, 21 Oct 2015 07:28:00 +0000
+    "%Y-%m-%dT%H:%M:%S%.f%:z",  // 2024-04-05T11:59:30.123456789+02:00
+];
+
+// Formats of local dates. A trailing `Z` or timezone abbreviation
+// (`CEST`) is removed before trying those formats.
+const LOCAL_FORMATS: [&str; 15] = [
+    "%Y-%m-%d %H:%M",         // 2023-12-03 17:33 (CEST)
+    "%d-%b-%Y %H:%M",         // 05-Apr-2024 11:59
+    "%Y-%b-%d %H:%M",         // 2021-May-25 20:15
+    "%Y-%m-%d %H:%M:%S",      // 2023-12-03 17:33:19 (CST)
+    "%d-%b-%Y %H:%M:%S",      // 05-Apr-2024 11:59:30
+    "%Y-%b-%d %H:%M:%S",      // 2021-May-25 20:15:46
+    "%Y/%m/%d %H:%M:%S",      // 2025/10/21 21:53:58
+    "%Y-%m-%dT%H:%M",         // 2025-10-20T14:17(Z)
+    "%Y-%m-%dT%H:%M:%S%.f",   // 2015-10-21T07:28:00(.5)(Z)
+    "%a, %d %b %Y %H:%M:%S",  // Wed, 21 Oct 2015 07:28:00 (GMT)
+    "%d-%m-%Y | %H:%M",       // 20-10-2025 | 13:52
+    "%B %d, %Y %H:%M",        // October 21, 2025 20:53
+    "%d-%m-%Y %H:%M",         // 21-10-2025 14:19
+    "%m/%d/%Y %I:%M %p",      // 5/31/2025 1:54 PM
+    "%A, %B %d, %Y %I:%M %p", // Saturday, May 31, 2025 1:54 PM
+];
+
+// Returns the offset of an unambiguous timezone abbreviation.
+// Ambiguous ones (`CST`, `IST`, ...) are not known and dates
+// printed with them are treated as local dates.
+fn abbreviation_offset(abbreviation: &str) -> Option<FixedOffset> {
+    let hours = match abbreviation {
+        "UTC" | "GMT" | "UT" | "WET" => 0,
+        "CET" | "WEST" | "BST" => 1,
+        "CEST" | "EET" => 2,
+        "EEST" | "MSK" => 3,
+        "HKT" | "AWST" => 8,
+        "JST" | "KST" => 9,
+        "AEST" => 10,
+        "AEDT" => 11,
+        "NZST" => 12,
+        "NZDT" => 13,
+        "EDT" => -4,
+        "EST" | "CDT" => -5,
+        "MDT" => -6,
+        "MST" | "PDT" => -7,
+        "PST" => -8,
+        _ => return None,
+    };
+    FixedOffset::east_opt(hours * 3600)
+}
+
+// Tries to parse `date` with the formats of local dates
+fn try_parse_local_date(date: &str) -> Option<NaiveDateTime> {
+    for pf in LOCAL_FORMATS {
+        match NaiveDateTime::parse_from_str(date, pf) {
+            Ok(d) => {
+                trace!("Successfully parsed date ({date}) with format '{pf}'");
+                return Some(d);
             }
+            Err(e) => trace!("Error while parsing date ({date}) with format '{pf}': {e}"),
         }
     }
     None
 }
 
+// Tries to parse a string that should contain a date with
+// arrays of known formats. Returns the local date and its
+// offset when the string gives one.
+pub(crate) fn try_parse_date(date: &str) -> Option<ParsedDate> {
+    if date.len() <= 3 || !date.contains(':') {
+        return None;
+    }
+
+    for pf in OFFSET_FORMATS {
+        match DateTime::parse_from_str(date, pf) {
+            Ok(d) => {
+                trace!("Successfully parsed date ({date}) with format '{pf}'");
+                return Some((d.naive_local(), Some(*d.offset())));
+            }
+            Err(e) => trace!("Error while parsing date ({date}) with format '{pf}': {e}"),
+        }
+    }
+
+    if let Some(d) = try_parse_local_date(date) {
+        return Some((d, None));
+    }
+
+    // 2025-10-20T14:17Z
+    if let Some(local) = date.strip_suffix('Z')
+        && local.ends_with(|c: char| c.is_ascii_digit())
+    {
+        return try_parse_local_date(local).map(|d| (d, FixedOffset::east_opt(0)));
+    }
+
+    // 2025-10-20 16:17 CEST
+    if let Some((local, abbreviation)) = date.rsplit_once(' ')
+        && !abbreviation.is_empty()
+        && abbreviation.bytes().all(|b| b.is_ascii_uppercase())
+    {
+        return try_parse_local_date(local.trim_end()).map(|d| (d, abbreviation_offset(abbreviation)));
+    }
+
+    None
+}
+
 // Tries to parse a date within date or size fields that
-// may be in reverse order in the html page
+// may be in reverse order in the html page and returns it
+// with the date and size fields in the right order.
 // reversed is true when we are already testing size instead of date
 // so there is no need to reverse twice.
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn get_date_from_inputs<'a>(date: &'a str, size: &'a str, reversed: bool) -> Option<(NaiveDateTime, &'a str)> {
+fn get_date_from_inputs<'a>(date: &'a str, size: &'a str, reversed: bool) -> Option<(ParsedDate, &'a str, &'a str)> {
     if let Some(parsed_date) = try_parse_date(date) {
-        Some((parsed_date, size))
+        Some((parsed_date, date, size))
     } else if !reversed {
-        if let Some((parsed_date, _)) = get_date_from_inputs(size, date, true) {
-            Some((parsed_date, date))
-        } else {
-            None
-        }
+        get_date_from_inputs(size, date, true)
     } else {
         None
     }
 }
 
-// @todo: be more accurate with the modifier that should
-// be 1000 for Kb and 1024 for KiB ?
+// Returns the date with its offset. Local dates are in UTC
+// until the default timezone of the directory is applied
+// (see `Entry::set_default_timezone()`)
+fn zoned_date((date, offset): ParsedDate) -> Option<DateTime<FixedOffset>> {
+    match offset {
+        Some(offset) => date.and_local_timezone(offset).single(),
+        None => Some(date.and_utc().fixed_offset()),
+    }
+}
+
+/// Unit system used to read the units of sizes printed by a listing.
+/// Units with an `i` (`KiB`, `MiB`) are always binary (IEC) whatever
+/// the unit system is: it only applies to ambiguous units (`k`, `kB`,
+/// `M`, ...).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnitSystem {
+    /// Binary units: 1k is 1024 bytes (IEC)
+    #[default]
+    Iec,
+
+    /// Decimal units: 1k is 1000 bytes (SI)
+    Si,
+}
+
+impl UnitSystem {
+    // Number of bytes of the `k` unit
+    fn base(self) -> u64 {
+        match self {
+            UnitSystem::Iec => 1024,
+            UnitSystem::Si => 1000,
+        }
+    }
+}
+
 // This will never fail because of the regex. It may return None
 // when `size` string does not contain any number and the captured
 // number with unit 1 in case it could not capture any unit modifier
 // ie: wrong units will not be detected and answer may be wrong.
+// Ambiguous units are read with `system`.
 // @todo: do we need to detect such error ?
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-fn capture_size_and_unit(size: &str) -> Option<(String, usize)> {
+fn capture_size_and_unit(size: &str, system: UnitSystem) -> Option<(String, u64)> {
     trace!("To be captured: {size}");
 
-    let captured_modifier: usize;
+    let captured_modifier: u64;
     let captured_size: String;
 
     if let Some(value) = SIZE_RE.captures(size) {
@@ -105,23 +240,29 @@ fn capture_size_and_unit(size: &str) -> Option<(String, usize)> {
         trace!("Captured some value: {value:?}");
         if value.get(1).is_some() {
             match_cap = 1;
-        } else if value.get(3).is_some() {
-            match_cap = 3;
-        } else if value.get(5).is_some() {
-            match_cap = 5;
+        } else if value.get(4).is_some() {
+            match_cap = 4;
+        } else if value.get(6).is_some() {
+            match_cap = 6;
         } else {
             return None;
         }
 
         trace!("match group: {match_cap}");
         if let Some(modifier) = value.get(match_cap + 1) {
+            // Only the first alternative of the regex captures the `i`
+            let base = if match_cap == 1 && value.get(3).is_some() {
+                UnitSystem::Iec.base()
+            } else {
+                system.base()
+            };
             captured_modifier = match modifier.as_str().chars().next() {
                 Some('b' | 'B') => 1,
-                Some('k' | 'K') => 1024,
-                Some('m' | 'M') => 1_048_576,
-                Some('g' | 'G') => 1_073_741_824,
-                Some('t' | 'T') => 1_099_511_627_776,
-                Some('p' | 'P') => 1_125_899_906_842_624,
+                Some('k' | 'K') => base,
+                Some('m' | 'M') => base.pow(2),
+                Some('g' | 'G') => base.pow(3),
+                Some('t' | 'T') => base.pow(4),
+                Some('p' | 'P') => base.pow(5),
                 _ => 0,
             };
         } else {
@@ -148,9 +289,10 @@ fn capture_size_and_unit(size: &str) -> Option<(String, usize)> {
 // new_size must contain a number with a dot ie: 423.3
 // real_size contains the value of a modifier: k or Kib stands for 1024 bytes
 // see capture_size_and_unit() function above.
+// Sizes bigger than `u64::MAX` saturate to that value.
 #[allow(clippy::cast_sign_loss)]
 #[allow(clippy::cast_possible_truncation)]
-fn parse_float_to_usize(new_size: &str, real_size: usize) -> usize {
+fn parse_float_to_u64(new_size: &str, real_size: u64) -> u64 {
     match new_size.parse::<f64>() {
         Ok(number) => {
             if number.signum().is_finite()
@@ -158,48 +300,66 @@ fn parse_float_to_usize(new_size: &str, real_size: usize) -> usize {
                 && number > -18_446_744_073_709_551_615.0
             {
                 // number is not Nan nor ∞
-                // We know that .abs() will return a positive value
-                // if number is greater than `usize::MAX` then number
-                // is truncated to usize::MAX
-                return real_size * ((number.abs() * 10.0) as usize) / 10;
+                // We know that .abs() will return a positive value.
+                // Tenths are computed with u128 so that the product
+                // can not overflow before being saturated
+                let tenths = (number.abs() * 10.0) as u128;
+                return u64::try_from(u128::from(real_size) * tenths / 10).unwrap_or(u64::MAX);
             }
             0
         }
         Err(e) => {
-            error!("error parsing '{new_size}' into usize: {e}");
+            error!("error parsing '{new_size}' into u64: {e}");
             0
         }
     }
 }
 
-fn parse_to_usize(new_size: &str, real_size: usize) -> usize {
-    match new_size.parse::<usize>() {
-        Ok(number) => real_size * number,
+// Sizes bigger than `u64::MAX` (the number itself or once
+// multiplied by `real_size`) saturate to that value
+fn parse_to_u64(new_size: &str, real_size: u64) -> u64 {
+    match new_size.parse::<u64>() {
+        Ok(number) => real_size.saturating_mul(number),
+        Err(e) if *e.kind() == IntErrorKind::PosOverflow => {
+            trace!("'{new_size}' is too big: saturating to u64::MAX");
+            if real_size == 0 {
+                0
+            } else {
+                u64::MAX
+            }
+        }
         Err(e) => {
-            error!("error parsing '{new_size}' into usize: {e}");
+            error!("error parsing '{new_size}' into u64: {e}");
             0
         }
     }
 }
 
-/// Returns the apparent size as a usize number.
+/// Returns the apparent size as a u64 number.
 /// It is not an accurate size as 42K results in
 /// 42 * 1024 = 43008 (the real size in bytes may
 /// be a bit greater or a bit lower to this)
-/// In case the size is greater than `usize::MAX`
-/// it may be truncated to that value
+/// In case the size is greater than `u64::MAX`
+/// it saturates to that value. Ambiguous
+/// units are binary ones (see `UnitSystem`).
 #[must_use]
+pub fn apparent_size(size: &str) -> u64 {
+    apparent_size_in(size, UnitSystem::Iec)
+}
+
+// Returns the apparent size as a u64 number reading
+// ambiguous units with `system`
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
-pub fn apparent_size(size: &str) -> usize {
+fn apparent_size_in(size: &str, system: UnitSystem) -> u64 {
     // Shortly determine if size is from a directory
     if size.contains('-') {
         return 0;
     }
 
-    let real_size: usize;
+    let real_size: u64;
     let new_size: String;
 
-    match capture_size_and_unit(&size.to_lowercase()) {
+    match capture_size_and_unit(&size.to_lowercase(), system) {
         Some((captured_size, captured_modifier)) => {
             trace!("Detected size: {captured_size}, modifier: {captured_modifier}");
             real_size = captured_modifier;
@@ -211,14 +371,47 @@ pub fn apparent_size(size: &str) -> usize {
     }
 
     if new_size.contains('.') {
-        return parse_float_to_usize(&new_size, real_size);
+        return parse_float_to_u64(&new_size, real_size);
     } else if !new_size.is_empty() {
-        return parse_to_usize(&new_size, real_size);
+        return parse_to_u64(&new_size, real_size);
     }
 
     0
 }
 
+// Returns the smallest and the biggest sizes in bytes that `size`
+// may stand for. A number is assumed to be rounded to half a unit
+// of its last printed digit (4.2M is between 4.15M and 4.25M) and
+// an integer number of bytes is exact. Returns None when `size`
+// does not fit in a u64 (no range can be given then).
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_precision_loss)]
+fn size_range(size: &str, system: UnitSystem) -> Option<(u64, u64)> {
+    if size.contains('-') {
+        return None;
+    }
+
+    let (number, modifier) = capture_size_and_unit(&size.to_lowercase(), system)?;
+    let number = number.trim();
+    if modifier == 1 && !number.contains('.') {
+        let exact = number.parse::<u64>().ok()?;
+        return Some((exact, exact));
+    }
+
+    let value = number.parse::<f64>().ok()?;
+    let decimals = number.split_once('.').map_or(0, |(_, decimals)| decimals.len());
+    let half = 0.5 / 10_f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX));
+    let max = ((value + half) * modifier as f64).floor();
+    // 2^64: float to integer casts would saturate to `u64::MAX`
+    if !max.is_finite() || max >= 18_446_744_073_709_551_616.0 {
+        trace!("'{size}' does not fit in a u64: no size range");
+        return None;
+    }
+    let min = ((value - half).max(0.0) * modifier as f64).ceil() as u64;
+    Some((min, max as u64))
+}
+
 impl Entry {
     /// Creates a new Entry
     #[must_use]
@@ -226,26 +419,72 @@ impl Entry {
         trace!("name: {name}, date: {date}, size: {size}, link: {link}");
         let name = name.to_string();
         let link = link.to_string();
-        let ndt_date: Option<NaiveDateTime>;
+        let parsed_date: Option<ParsedDate>;
+        let guessed_date: &str;
         let guessed_size: &str;
 
-        if let Some((date, parsed_size)) = get_date_from_inputs(date, size, false) {
-            ndt_date = Some(date);
+        if let Some((date, parsed_date_str, parsed_size)) = get_date_from_inputs(date, size, false) {
+            parsed_date = Some(date);
+            guessed_date = parsed_date_str;
             guessed_size = parsed_size;
         } else {
-            ndt_date = None;
+            parsed_date = None;
+            guessed_date = date;
             guessed_size = size; // size here is assumed to be "correct" somehow
         }
-        let date = ndt_date;
+
+        let date_has_offset = parsed_date.is_some_and(|(_, offset)| offset.is_some());
+        let date = parsed_date.and_then(zoned_date);
         trace!("name: {name}, date: {date:?}, size: {guessed_size}, link: {link}");
 
         Entry {
             name,
             link,
             date,
+            date_has_offset,
+            apparent_date: guessed_date.to_string(),
             apparent_size: guessed_size.to_string(),
+            unit_system: UnitSystem::Iec,
             size: apparent_size(guessed_size),
+            description: None,
+            extra_columns: BTreeMap::new(),
+            url: None,
+            checksum: None,
+        }
+    }
+
+    /// Resolves the link (or the name when there is no link) of this
+    /// entry against `base` (the url of the listing) with the same
+    /// rules than `cd()`. Links that only contain a query or a fragment
+    /// (sorting links such as `?C=M;O=A`) and links to other schemes
+    /// than http(s) (`mailto:`, `javascript:`) are not resolved.
+    pub(crate) fn resolve_url(&mut self, base: &Url) {
+        let link = if self.link.is_empty() {
+            &self.name
+        } else {
+            &self.link
+        };
+
+        if link.starts_with('?') || link.starts_with('#') {
+            trace!("Not resolving query or fragment only link '{link}'");
+            return;
         }
+
+        match base.join(link) {
+            Ok(mut url) if matches!(url.scheme(), "http" | "https") => {
+                url.set_fragment(None);
+                self.url = Some(url);
+            }
+            Ok(url) => trace!("Not resolving '{url}': not an http(s) url"),
+            Err(e) => trace!("Unable to join '{link}' to {base}: {e}"),
+        }
+    }
+
+    /// Returns the date of the Entry as an &str as read on the
+    /// original website (whether it could be parsed or not)
+    #[must_use]
+    pub fn apparent_date(&self) -> &str {
+        &self.apparent_date
     }
 
     /// Returns the size of the Entry as an &str as read on the
@@ -257,14 +496,56 @@ impl Entry {
         &self.apparent_size
     }
 
-    /// Gets the size of the file as a usize number. This size has
+    /// Gets the size of the file as a u64 number. This size has
     /// been guessed and calculated when parsing each line of data
     /// scraping the website.
     #[must_use]
-    pub fn size(&self) -> usize {
+    pub fn size(&self) -> u64 {
         self.size
     }
 
+    /// Returns the size of the file as a u64 number only when
+    /// the website gave it exactly in bytes (without any unit
+    /// other than `B`). Returns None when that size does not fit
+    /// in a u64 (`size()` saturates to `u64::MAX` instead).
+    #[must_use]
+    pub fn exact_size(&self) -> Option<u64> {
+        self.size_range().filter(|range| range.start() == range.end()).map(|range| *range.start())
+    }
+
+    /// Tells whether the size has been given exactly in bytes by
+    /// the website and not rounded with a unit (`4.2M`)
+    #[must_use]
+    pub fn is_size_exact(&self) -> bool {
+        self.exact_size().is_some()
+    }
+
+    /// Returns the range of the sizes in bytes that the apparent size
+    /// may stand for: `4.2M` (rounded to a tenth) is any size between
+    /// 4.15M and 4.25M whereas an exact size is a single value range.
+    /// Returns None when the website gave no size (directories) or
+    /// when the size does not fit in a u64.
+    #[must_use]
+    pub fn size_range(&self) -> Option<RangeInclusive<u64>> {
+        size_range(&self.apparent_size, self.unit_system).map(|(min, max)| min..=max)
+    }
+
+    /// Returns the unit system used to read the ambiguous units
+    /// (`k`, `kB`, `M`, ...) of the apparent size. It is the one
+    /// of the scraper that understood the listing.
+    #[must_use]
+    pub fn unit_system(&self) -> UnitSystem {
+        self.unit_system
+    }
+
+    // Reads again the apparent size with `system`
+    pub(crate) fn set_unit_system(&mut self, system: UnitSystem) {
+        if self.unit_system != system {
+            self.unit_system = system;
+            self.size = apparent_size_in(&self.apparent_size, system);
+        }
+    }
+
     /// Returns the name of the file or directory
     #[must_use]
     pub fn name(&self) -> &str {
@@ -277,12 +558,98 @@ impl Entry {
         &self.link
     }
 
-    /// Returns the date of the file or directory
+    /// Returns the date of the file or directory with its offset.
+    /// When the listing prints local dates without any offset, the
+    /// default timezone of the directory is used (UTC unless set with
+    /// `HttpDirectoryBuilder::timezone()`).
     #[must_use]
-    pub fn date(&self) -> Option<NaiveDateTime> {
+    pub fn date(&self) -> Option<DateTime<FixedOffset>> {
         self.date
     }
 
+    /// Tells whether the offset of the date has been printed by the
+    /// listing (and not taken from the default timezone)
+    #[must_use]
+    pub fn date_has_offset(&self) -> bool {
+        self.date_has_offset
+    }
+
+    // Parses a date that the built-in formats did not understand
+    // with the user formats and month names of `parser` (the date
+    // may have been printed in place of the size) and then applies
+    // the default timezone of `parser` if any
+    pub(crate) fn parse_date(&mut self, parser: &DateParser) {
+        if self.date.is_none() {
+            if let Some(parsed) = parser.parse(&self.apparent_date) {
+                self.date_has_offset = parsed.1.is_some();
+                self.date = zoned_date(parsed);
+            } else if let Some(parsed) = parser.parse(&self.apparent_size) {
+                std::mem::swap(&mut self.apparent_date, &mut self.apparent_size);
+                self.size = apparent_size_in(&self.apparent_size, self.unit_system);
+                self.date_has_offset = parsed.1.is_some();
+                self.date = zoned_date(parsed);
+            }
+        }
+
+        if let Some(timezone) = parser.timezone() {
+            self.set_default_timezone(timezone);
+        }
+    }
+
+    // Interprets a local date (printed without any offset) as
+    // a date of `timezone`
+    pub(crate) fn set_default_timezone(&mut self, timezone: FixedOffset) {
+        if !self.date_has_offset {
+            self.date = self.date.and_then(|date| date.naive_local().and_local_timezone(timezone).single());
+        }
+    }
+
+    /// Returns the description of the file or directory if the
+    /// listing has one (Apache's "Description" column)
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the other columns printed by the listing for this
+    /// file or directory (type, owner, ...) indexed by their header
+    #[must_use]
+    pub fn extra_columns(&self) -> &BTreeMap<String, String> {
+        &self.extra_columns
+    }
+
+    /// Returns the value of the column named `header` (compared
+    /// without any case) among the other columns of the listing
+    #[must_use]
+    pub fn extra_column(&self, header: &str) -> Option<&str> {
+        self.extra_columns.iter().find(|(name, _)| name.eq_ignore_ascii_case(header)).map(|(_, value)| value.as_str())
+    }
+
+    // Sets the description and the other columns read in the listing
+    pub(crate) fn set_columns(&mut self, description: Option<String>, extra_columns: BTreeMap<String, String>) {
+        self.description = description;
+        self.extra_columns = extra_columns;
+    }
+
+    /// Returns the absolute url of the file or directory if it
+    /// could be resolved when scraping the listing
+    #[must_use]
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    /// Returns the expected digest of the file if it has been
+    /// found in a checksum manifest (see `HttpDirectory::load_checksums()`)
+    #[must_use]
+    pub fn checksum(&self) -> Option<&Checksum> {
+        self.checksum.as_ref()
+    }
+
+    // Sets the expected digest of the file
+    pub(crate) fn set_checksum(&mut self, checksum: Checksum) {
+        self.checksum = Some(checksum);
+    }
+
     /// Compares two `Entry` by name and returns an `Ordering`
     #[must_use]
     pub fn cmp_by_name(&self, other: &Self, ascending: bool) -> Ordering {
@@ -325,7 +692,14 @@ impl fmt::Display for Entry {
 
 #[cfg(test)]
 mod tests {
-    use {super::Entry, std::cmp::Ordering, unwrap_unreachable::UnwrapUnreachable};
+    use {
+        super::{Entry, UnitSystem},
+        crate::date::{DateParser, MonthNames},
+        chrono::FixedOffset,
+        std::cmp::Ordering,
+        unwrap_unreachable::UnwrapUnreachable,
+        url::Url,
+    };
     #[test]
     fn test_apparent_size_float() {
         let entry = Entry::new("name", "link", "2025-05-20 20:19", "5.0K");
@@ -424,6 +798,43 @@ mod tests {
         assert_eq!(entry.size, 4);
     }
 
+    #[test]
+    fn test_apparent_size_pib_float() {
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "1.5 PiB");
+
+        assert_eq!(entry.size, 1_688_849_860_263_936);
+    }
+
+    #[test]
+    fn test_apparent_size_pib_largest() {
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "16383 PiB");
+
+        assert_eq!(entry.size, 18_445_618_173_802_708_992);
+    }
+
+    #[test]
+    fn test_apparent_size_pib_saturates() {
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "16384 PiB");
+        assert_eq!(entry.size, u64::MAX);
+
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "20000.5 PiB");
+        assert_eq!(entry.size, u64::MAX);
+
+        assert_eq!(entry.size_range(), None);
+
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "99999999999999999999999");
+        assert_eq!(entry.size, u64::MAX);
+        assert_eq!(entry.exact_size(), None);
+        assert_eq!(entry.size_range(), None);
+    }
+
+    #[test]
+    fn test_size_range_pib() {
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "2.0 PiB");
+
+        assert_eq!(entry.size_range(), Some(2_195_504_818_343_117..=2_308_094_809_027_379));
+    }
+
     #[test]
     fn test_apparent_size_zero() {
         let entry = Entry::new("name", "link", "2025-05-20 20:19", "0");
@@ -447,8 +858,8 @@ mod tests {
 
     #[test]
     fn test_capture_size_and_unit() {
-        use crate::entry::capture_size_and_unit;
-        if let Some((size, unit)) = capture_size_and_unit("12 Kib") {
+        use crate::entry::{UnitSystem, capture_size_and_unit};
+        if let Some((size, unit)) = capture_size_and_unit("12 Kib", UnitSystem::Si) {
             assert_eq!(size, "12".to_string());
             assert_eq!(unit, 1024);
         }
@@ -456,16 +867,16 @@ mod tests {
 
     #[test]
     fn test_capture_empty_size_and_unit() {
-        use crate::entry::capture_size_and_unit;
-        if let Some((size, unit)) = capture_size_and_unit("") {
+        use crate::entry::{UnitSystem, capture_size_and_unit};
+        if let Some((size, unit)) = capture_size_and_unit("", UnitSystem::Iec) {
             panic!("This test should return None. We got {size} and {unit} !");
         }
     }
 
     #[test]
     fn test_capture_wrong_size_and_unit() {
-        use crate::entry::capture_size_and_unit;
-        if let Some((size, unit)) = capture_size_and_unit("Not_A_Size") {
+        use crate::entry::{UnitSystem, capture_size_and_unit};
+        if let Some((size, unit)) = capture_size_and_unit("Not_A_Size", UnitSystem::Iec) {
             panic!("This test should return None. We got {size} and {unit} !");
         }
     }
@@ -531,6 +942,53 @@ mod tests {
         assert_eq!(entry2.cmp_by_size(&entry1, false), Ordering::Greater);
     }
 
+    #[test]
+    fn test_exact_size() {
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", "4096").exact_size(), Some(4096));
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", "4.0k").exact_size(), None);
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", " - ").exact_size(), None);
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", "").exact_size(), None);
+    }
+
+    #[test]
+    fn test_unit_system() {
+        let mut entry = Entry::new("name", "link", "2025-05-21 03:45", "4.2 kB");
+        assert_eq!(entry.unit_system(), UnitSystem::Iec);
+        assert_eq!(entry.size(), 4300);
+
+        entry.set_unit_system(UnitSystem::Si);
+        assert_eq!(entry.unit_system(), UnitSystem::Si);
+        assert_eq!(entry.size(), 4200);
+
+        // Binary units are never decimal ones
+        let mut entry = Entry::new("name", "link", "2025-05-21 03:45", "1 MiB");
+        entry.set_unit_system(UnitSystem::Si);
+        assert_eq!(entry.size(), 1_048_576);
+
+        let mut entry = Entry::new("name", "link", "2025-05-21 03:45", "1G");
+        entry.set_unit_system(UnitSystem::Si);
+        assert_eq!(entry.size(), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_size_range() {
+        let entry = Entry::new("name", "link", "2025-05-21 03:45", "4.2M");
+        assert_eq!(entry.size_range(), Some(4_351_591..=4_456_448));
+        assert!(!entry.is_size_exact());
+
+        let mut entry = Entry::new("name", "link", "2025-05-21 03:45", "5K");
+        entry.set_unit_system(UnitSystem::Si);
+        assert_eq!(entry.size_range(), Some(4500..=5500));
+
+        let entry = Entry::new("name", "link", "2025-05-21 03:45", "886 B");
+        assert_eq!(entry.size_range(), Some(886..=886));
+        assert!(entry.is_size_exact());
+        assert_eq!(entry.exact_size(), Some(886));
+
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", " - ").size_range(), None);
+        assert_eq!(Entry::new("name", "link", "2025-05-21 03:45", "").size_range(), None);
+    }
+
     #[test]
     fn test_date_format_1() {
         let entry = Entry::new("name", "link", "2023-12-03 17:33", "4.0 kib");
@@ -585,6 +1043,8 @@ mod tests {
         let entry = Entry::new("name", "link", "05/31/2025 01:54:45 PM +00:00", "4.0 kib");
         let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
         assert_eq!(date_str, "2025-05-31 13:54");
+        assert_eq!(entry.date.unreachable().offset().to_string(), "+00:00");
+        assert!(entry.date_has_offset());
     }
 
     #[test]
@@ -592,6 +1052,7 @@ mod tests {
         let entry = Entry::new("name", "link", "2025-10-20T14:17Z", "4.0 kib");
         let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
         assert_eq!(date_str, "2025-10-20 14:17");
+        assert!(entry.date_has_offset());
     }
 
     #[test]
@@ -606,6 +1067,8 @@ mod tests {
         let entry = Entry::new("name", "link", "2025-10-20 16:17 CEST", "4.0 kib");
         let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
         assert_eq!(date_str, "2025-10-20 16:17");
+        assert_eq!(entry.date.unreachable().offset().to_string(), "+02:00");
+        assert!(entry.date_has_offset());
     }
 
     #[test]
@@ -613,6 +1076,8 @@ mod tests {
         let entry = Entry::new("name", "link", "2025-09-06 18:15:23 CST", "4.0 kib");
         let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
         assert_eq!(date_str, "2025-09-06 18:15");
+        // CST is ambiguous: the date is a local one
+        assert!(!entry.date_has_offset());
     }
 
     #[test]
@@ -627,6 +1092,7 @@ mod tests {
         let entry = Entry::new("name", "link", "06 Sep 2025 10:15:23 +0000", "4.0 kib");
         let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
         assert_eq!(date_str, "2025-09-06 10:15");
+        assert!(entry.date_has_offset());
     }
 
     #[test]
@@ -636,9 +1102,117 @@ mod tests {
         assert_eq!(date_str, "2025-10-21 14:19");
     }
 
+    #[test]
+    fn test_date_format_12_hour_clock() {
+        let entry = Entry::new("name", "link", "5/31/2025 1:54 PM", "12345");
+        let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(date_str, "2025-05-31 13:54");
+        assert!(!entry.date_has_offset());
+
+        let entry = Entry::new("name", "link", "Monday, January 06, 2025 12:30 AM", "12345");
+        let date_str = entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(date_str, "2025-01-06 00:30");
+    }
+
+    #[test]
+    fn test_date_offsets_compare_instants() {
+        let paris = Entry::new("name", "link", "2025-10-20 16:17 CEST", "4.0 kib");
+        let utc = Entry::new("name", "link", "2025-10-20T14:17Z", "4.0 kib");
+        let new_york = Entry::new("name", "link", "2025-10-20 10:18 EDT", "4.0 kib");
+
+        assert_eq!(paris.date(), utc.date());
+        assert_eq!(paris.cmp_by_date(&new_york, true), Ordering::Less);
+        assert_eq!(format!("{paris}"), "  4.0 kib  2025-10-20 16:17  name");
+
+        let rfc3339 = Entry::new("name", "link", "2025-10-20T16:17:00.123456789+02:00", "4.0 kib");
+        let rfc3339_utc = Entry::new("name", "link", "2025-10-20T14:17:00.123456789Z", "4.0 kib");
+        assert_eq!(rfc3339.date(), rfc3339_utc.date());
+        assert!(rfc3339.date_has_offset() && rfc3339_utc.date_has_offset());
+        assert_eq!(
+            rfc3339.date().map(|date| date.to_rfc3339()),
+            Some("2025-10-20T16:17:00.123456789+02:00".to_string())
+        );
+
+        let rfc1123 = Entry::new("name", "link", "Mon, 20 Oct 2025 14:17:00 GMT", "4.0 kib");
+        assert_eq!(rfc1123.date().map(|date| date.to_rfc3339()), Some("2025-10-20T14:17:00+00:00".to_string()));
+        assert!(rfc1123.date_has_offset());
+    }
+
+    #[test]
+    fn test_set_default_timezone() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unreachable();
+        let mut local = Entry::new("name", "link", "2025-10-20 16:17", "4.0 kib");
+        let mut zoned = Entry::new("name", "link", "06 Sep 2025 10:15:23 +0000", "4.0 kib");
+        assert_eq!(local.date.unreachable().offset().to_string(), "+00:00");
+        assert!(!local.date_has_offset());
+
+        local.set_default_timezone(tokyo);
+        zoned.set_default_timezone(tokyo);
+        assert_eq!(local.date.unreachable().to_rfc3339(), "2025-10-20T16:17:00+09:00");
+        assert_eq!(zoned.date.unreachable().to_rfc3339(), "2025-09-06T10:15:23+00:00");
+    }
+
+    #[test]
+    fn test_parse_date_with_user_settings() {
+        let mut parser = DateParser::default();
+        parser.add_month_names(MonthNames::german());
+        parser.add_format("%d.%m.%Y %H.%M");
+
+        let mut entry = Entry::new("name", "link", "05-Mär-2024 11:59", "4.0 kib");
+        assert!(entry.date.is_none());
+        entry.parse_date(&parser);
+        assert_eq!(entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string(), "2024-03-05 11:59");
+        assert_eq!(entry.apparent_date(), "05-Mär-2024 11:59");
+
+        // Date printed in place of the size
+        let mut entry = Entry::new("name", "link", "12.0K", "05.03.2024 11.59");
+        entry.parse_date(&parser);
+        assert_eq!(entry.date.unreachable().format("%Y-%m-%d %H:%M").to_string(), "2024-03-05 11:59");
+        assert_eq!(entry.apparent_size(), "12.0K");
+        assert_eq!(entry.size(), 12_288);
+    }
+
     #[test]
     fn test_date_not_a_format() {
         let entry = Entry::new("name", "link", "21-2025-10, 14:19", "4.0 kib");
         assert!(entry.date.is_none());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_entry_serde_round_trip() {
+        let entry = Entry::new("name", "link", "2025-05-20 20:19", "5.0K");
+
+        let json = serde_json::to_string(&entry).unreachable();
+        assert_eq!(
+            json,
+            r#"{"name":"name","link":"link","date":"2025-05-20T20:19:00Z","date_has_offset":false,"apparent_date":"2025-05-20 20:19","apparent_size":"5.0K","unit_system":"Iec","size":5120,"description":null,"extra_columns":{},"url":null,"checksum":null}"#
+        );
+
+        let back: Entry = serde_json::from_str(&json).unreachable();
+        assert_eq!(back.name(), "name");
+        assert_eq!(back.link(), "link");
+        assert_eq!(back.date(), entry.date());
+        assert_eq!(back.apparent_size(), "5.0K");
+        assert_eq!(back.size(), 5120);
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let base = Url::parse("https://example.org/debian/dists/").unreachable();
+        let resolved = |name: &str, link: &str| {
+            let mut entry = Entry::new(name, link, "", "-");
+            entry.resolve_url(&base);
+            entry.url().map(Url::to_string)
+        };
+
+        assert_eq!(resolved("foo/", "foo/"), Some("https://example.org/debian/dists/foo/".to_string()));
+        assert_eq!(resolved("foo/", "/debian/foo/"), Some("https://example.org/debian/foo/".to_string()));
+        assert_eq!(resolved("foo", "https://mirror.org/foo"), Some("https://mirror.org/foo".to_string()));
+        assert_eq!(resolved("foo bar", ""), Some("https://example.org/debian/dists/foo%20bar".to_string()));
+        assert_eq!(resolved("file", "file#top"), Some("https://example.org/debian/dists/file".to_string()));
+        assert_eq!(resolved("Name", "?C=N;O=D"), None);
+        assert_eq!(resolved("top", "#top"), None);
+        assert_eq!(resolved("mail", "mailto:root@example.org"), None);
+    }
 }
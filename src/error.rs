@@ -15,12 +15,26 @@ pub enum HttpDirError {
         source: reqwest::Error,
     },
 
+    /// A header given to `HttpDirectoryBuilder` has an invalid name or value
+    #[snafu(display("Invalid header '{name}' for the request client"))]
+    InvalidHeader {
+        name: String,
+    },
+
     #[snafu(display("Error retrieving content from '{url}': {status_code}"))]
     HttpResponse {
         url: String,
         status_code: reqwest::StatusCode,
     },
 
+    /// The body of the response is bigger than the maximum
+    /// body size set with `HttpDirectoryBuilder`
+    #[snafu(display("Content from '{url}' is bigger than {max_size} bytes"))]
+    BodyTooLarge {
+        url: String,
+        max_size: u64,
+    },
+
     /// Errors in regular expression (`filter_by_name()` may
     /// fail when used with a bad regular expression)
     #[snafu(display("Regular expression failed to compile '{regex}':\n -> {source}"))]
@@ -37,10 +51,94 @@ pub enum HttpDirError {
         source: url::ParseError,
     },
 
+    /// A JSON listing (nginx `autoindex_format json;`) could not be parsed
+    #[snafu(display("Error while parsing JSON listing:\n -> {source}"))]
+    Json {
+        source: serde_json::Error,
+    },
+
+    /// An XML listing (nginx `autoindex_format xml;` or S3 bucket)
+    /// could not be parsed
+    #[snafu(display("Error while parsing XML listing:\n -> {source}"))]
+    Xml {
+        source: roxmltree::Error,
+    },
+
+    /// A paginated listing (S3 bucket) has more pages than the
+    /// maximum set with `HttpDirectoryBuilder::max_pages()`
+    #[snafu(display("Listing of '{url}' has more than {max_pages} pages"))]
+    TooManyPages {
+        url: String,
+        max_pages: usize,
+    },
+
+    /// A paginated listing gives again a page already retrieved
+    /// as its next page (its server cycles over its tokens)
+    #[snafu(display("Listing of '{url}' loops back to page '{page}'"))]
+    PaginationLoop {
+        url: String,
+        page: String,
+    },
+
     #[snafu(display("Error while parsing content with selector '{selector}'"))]
     Selector {
         selector: String,
     },
+
+    /// `download()` has been called with an entry that is not
+    /// a file or whose url could not be resolved
+    #[snafu(display("'{name}' is not a file with an url that could be downloaded"))]
+    NotDownloadable {
+        name: String,
+    },
+
+    /// Input / output errors while writing a downloaded file
+    #[snafu(display("Input / output error on '{path}':\n -> {source}"))]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    /// The server answered a `Range` request with a part of the
+    /// file that does not start where the partial file ends
+    #[snafu(display("'{url}' sent range '{content_range}' instead of the bytes from {offset}"))]
+    UnexpectedRange {
+        url: String,
+        offset: u64,
+        content_range: String,
+    },
+
+    /// The length of a downloaded file is not the exact size
+    /// given by the listing
+    #[snafu(display("Downloaded file '{path}' is {actual} bytes long instead of {expected} bytes"))]
+    SizeMismatch {
+        path: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// Offline mode of the disk cache is enabled and the
+    /// requested url has never been cached
+    #[snafu(display("'{url}' is not in the disk cache (offline mode)"))]
+    OfflineCacheMiss {
+        url: String,
+    },
+
+    /// `verify()` has been called with an entry that has no
+    /// expected digest (see `HttpDirectory::load_checksums()`)
+    #[snafu(display("'{name}' has no checksum to verify against"))]
+    MissingChecksum {
+        name: String,
+    },
+
+    /// The digest of a downloaded file is not the one given
+    /// by the checksum manifest
+    #[snafu(display("Checksum of '{path}' is {actual} instead of {expected}"))]
+    ChecksumMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, HttpDirError>;
@@ -94,6 +192,46 @@ impl<T> ParseResultExt<T> for std::result::Result<T, url::ParseError> {
     }
 }
 
+// Trait helper for input / output errors
+pub(crate) trait IoResultExt<T> {
+    fn with_path(self, path: &std::path::Path) -> Result<T>;
+}
+
+impl<T> IoResultExt<T> for std::result::Result<T, std::io::Error> {
+    fn with_path(self, path: &std::path::Path) -> Result<T> {
+        self.map_err(|source| HttpDirError::Io {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+}
+
+// Trait helper for JSON errors
+pub(crate) trait JsonResultExt<T> {
+    fn with_json(self) -> Result<T>;
+}
+
+impl<T> JsonResultExt<T> for std::result::Result<T, serde_json::Error> {
+    fn with_json(self) -> Result<T> {
+        self.map_err(|source| HttpDirError::Json {
+            source,
+        })
+    }
+}
+
+// Trait helper for XML errors
+pub(crate) trait XmlResultExt<T> {
+    fn with_xml(self) -> Result<T>;
+}
+
+impl<T> XmlResultExt<T> for std::result::Result<T, roxmltree::Error> {
+    fn with_xml(self) -> Result<T> {
+        self.map_err(|source| HttpDirError::Xml {
+            source,
+        })
+    }
+}
+
 // Trait helper for Selector errors
 pub(crate) trait SelectorResultExt<T> {
     fn with_selector(self, selector: &str) -> Result<T>;
@@ -0,0 +1,341 @@
+use crate::error::{HttpDirError, ParseResultExt, Result};
+use futures::{
+    StreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream},
+};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::fmt;
+use tracing::error;
+use url::Url;
+
+/// Response of a `Fetcher`: the HTTP status code, the headers and
+/// the decoded body of the requested url
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchResponse {
+    /// HTTP status code of the response
+    pub status: u16,
+
+    /// Headers of the response as (name, value) pairs
+    pub headers: Vec<(String, String)>,
+
+    /// Decoded body of the response
+    pub body: String,
+}
+
+impl FetchResponse {
+    /// Returns a new `FetchResponse` with `status`, `body` and no headers
+    #[must_use]
+    pub fn new(status: u16, body: &str) -> Self {
+        FetchResponse {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    /// Adds the header `name` with `value` to this response
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the value of the first header named `name`
+    /// (case insensitive) if any
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// Streamed response of a `Fetcher`: the HTTP status code, the
+/// headers and the body as a stream of chunks of bytes
+pub struct FetchStream {
+    /// HTTP status code of the response (206 for a partial content)
+    pub status: u16,
+
+    /// Headers of the response as (name, value) pairs
+    pub headers: Vec<(String, String)>,
+
+    /// Body of the response as a stream of chunks of bytes
+    pub body: BoxStream<'static, Result<Vec<u8>>>,
+}
+
+impl FetchStream {
+    /// Returns the value of the first header named `name`
+    /// (case insensitive) if any
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+impl fmt::Debug for FetchStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchStream")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+// Returns the value of the first header named `name` (case
+// insensitive) within `headers`
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+}
+
+/// A `Fetcher` retrieves the content of an url. It is the transport
+/// used by `HttpDirectory` to get listings. The default one uses
+/// reqwest and `InMemoryFetcher` serves canned bodies.
+///
+/// ```rust
+///   use futures::future::BoxFuture;
+///   use httpdirectory::{error::Result, fetcher::{FetchResponse, Fetcher}};
+///
+///   #[derive(Debug)]
+///   struct Static;
+///
+///   impl Fetcher for Static {
+///       fn fetch<'a>(&'a self, _url: &'a str) -> BoxFuture<'a, Result<FetchResponse>> {
+///           Box::pin(async { Ok(FetchResponse::new(200, "<ul><li><a href=\"file\">file</a></li></ul>")) })
+///       }
+///   }
+/// ```
+pub trait Fetcher: fmt::Debug + Send + Sync {
+    /// Fetches `url` and returns its status, headers and body.
+    /// A non 200 HTTP status code is not an error at this level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` could not be fetched at all
+    fn fetch<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse>>;
+
+    /// Fetches `url` sending the additional request `headers` (such
+    /// as `If-None-Match` or `If-Modified-Since` for conditional
+    /// requests that may be answered with a 304 HTTP status code).
+    /// The default implementation ignores `headers` and calls `fetch()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` could not be fetched at all
+    fn fetch_with_headers<'a>(
+        &'a self,
+        url: &'a str,
+        _headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<FetchResponse>> {
+        self.fetch(url)
+    }
+
+    /// Fetches `url` as a stream of bytes starting at byte `offset`
+    /// (a `Range` request answered with a 206 HTTP status code when
+    /// the server supports it). The default implementation does not
+    /// support ranges: it returns the whole body from `fetch()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` could not be fetched at all
+    fn fetch_stream<'a>(&'a self, url: &'a str, _offset: u64) -> BoxFuture<'a, Result<FetchStream>> {
+        Box::pin(async move {
+            let response = self.fetch(url).await?;
+            let body = response.body.into_bytes();
+
+            Ok(FetchStream {
+                status: response.status,
+                headers: response.headers,
+                body: stream::once(async move { Ok(body) }).boxed(),
+            })
+        })
+    }
+}
+
+/// `Fetcher` that serves canned responses from memory without
+/// any network access. Urls that are not known get a 404 HTTP
+/// status code.
+///
+/// ```rust
+///   use httpdirectory::{fetcher::InMemoryFetcher, httpdirectory::HttpDirectory};
+///   async fn in_memory_example() {
+///     let fetcher = InMemoryFetcher::new()
+///         .with_body("http://example.org/", r#"<ul><li><a href="dir/">dir/</a></li></ul>"#)
+///         .with_body("http://example.org/dir/", r#"<ul><li><a href="file">file</a></li></ul>"#);
+///     if let Ok(httpdir) = HttpDirectory::with_fetcher("http://example.org/", fetcher).await {
+///         println!("{:?}", httpdir.cd("dir/").await);
+///     }
+///   }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryFetcher {
+    responses: HashMap<String, FetchResponse>,
+}
+
+// Normalizes `url` the same way urls are built by `cd()`
+fn normalize(url: &str) -> String {
+    Url::parse(url).map_or_else(|_| url.to_string(), |url| url.to_string())
+}
+
+impl InMemoryFetcher {
+    /// Returns an empty `InMemoryFetcher`
+    #[must_use]
+    pub fn new() -> Self {
+        InMemoryFetcher::default()
+    }
+
+    /// Serves `body` with a 200 HTTP status code for `url`
+    #[must_use]
+    pub fn with_body(self, url: &str, body: &str) -> Self {
+        self.with_response(url, FetchResponse::new(200, body))
+    }
+
+    /// Serves `response` for `url`
+    #[must_use]
+    pub fn with_response(mut self, url: &str, response: FetchResponse) -> Self {
+        self.insert(url, response);
+        self
+    }
+
+    /// Inserts (or replaces) the `response` served for `url`
+    pub fn insert(&mut self, url: &str, response: FetchResponse) {
+        self.responses.insert(normalize(url), response);
+    }
+}
+
+impl Fetcher for InMemoryFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse>> {
+        let response = self.responses.get(&normalize(url)).cloned().unwrap_or_else(|| FetchResponse::new(404, ""));
+        Box::pin(async move { Ok(response) })
+    }
+
+    /// Answers conditional requests with a 304 HTTP status code when
+    /// `If-None-Match` or `If-Modified-Since` matches the `ETag` or
+    /// `Last-Modified` header of the canned response
+    fn fetch_with_headers<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<FetchResponse>> {
+        Box::pin(async move {
+            let response = self.fetch(url).await?;
+            let not_modified = response.status == 200
+                && [("If-None-Match", "ETag"), ("If-Modified-Since", "Last-Modified")].iter().any(
+                    |(request_header, response_header)| {
+                        find_header(headers, request_header)
+                            .is_some_and(|value| response.header(response_header) == Some(value))
+                    },
+                );
+
+            if not_modified {
+                Ok(FetchResponse {
+                    status: 304,
+                    headers: response.headers,
+                    body: String::new(),
+                })
+            } else {
+                Ok(response)
+            }
+        })
+    }
+
+    /// Serves ranges of bodies with a 206 HTTP status code (or 416
+    /// when `offset` is beyond the end of the body)
+    fn fetch_stream<'a>(&'a self, url: &'a str, offset: u64) -> BoxFuture<'a, Result<FetchStream>> {
+        Box::pin(async move {
+            let mut response = self.fetch(url).await?;
+            let mut body = response.body.into_bytes();
+            let length = body.len() as u64;
+
+            if offset > 0 && response.status == 200 {
+                if offset >= length {
+                    response.status = 416;
+                    response.headers.push(("Content-Range".to_string(), format!("bytes */{length}")));
+                    body.clear();
+                } else {
+                    response.status = 206;
+                    response
+                        .headers
+                        .push(("Content-Range".to_string(), format!("bytes {offset}-{}/{length}", length - 1)));
+                    body.drain(..usize::try_from(offset).unwrap_or(usize::MAX));
+                }
+            }
+
+            Ok(FetchStream {
+                status: response.status,
+                headers: response.headers,
+                body: stream::once(async move { Ok(body) }).boxed(),
+            })
+        })
+    }
+}
+
+// Fetches `url` with `fetcher` and returns its body if the
+// HTTP status code is 200
+pub(crate) async fn fetch_text(fetcher: &dyn Fetcher, url: &str) -> Result<String> {
+    Url::parse(url).with_url(url)?;
+
+    let response = fetcher.fetch(url).await?;
+    if response.status == 200 {
+        Ok(response.body)
+    } else {
+        error!("Error while retrieving url {url} content: {}", response.status);
+        Err(status_error(url, response.status))
+    }
+}
+
+// Returns the error of an unexpected HTTP `status` code for `url`
+pub(crate) fn status_error(url: &str, status: u16) -> HttpDirError {
+    HttpDirError::HttpResponse {
+        url: url.to_string(),
+        status_code: StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FetchResponse, Fetcher, InMemoryFetcher, fetch_text};
+
+    #[test]
+    fn test_fetch_response_header() {
+        let response = FetchResponse::new(200, "body").with_header("Content-Type", "text/html");
+
+        assert_eq!(response.header("content-type"), Some("text/html"));
+        assert_eq!(response.header("etag"), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_fetcher_conditional() {
+        let fetcher = InMemoryFetcher::new()
+            .with_response("http://example.org/", FetchResponse::new(200, "hello").with_header("ETag", "\"v1\""));
+        let fetch = |value: &str| {
+            let headers = vec![("If-None-Match".to_string(), value.to_string())];
+            let fetcher = fetcher.clone();
+            async move { fetcher.fetch_with_headers("http://example.org/", &headers).await.map(|r| r.status).ok() }
+        };
+
+        assert_eq!(fetch("\"v1\"").await, Some(304));
+        assert_eq!(fetch("\"v0\"").await, Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_fetcher() {
+        let fetcher = InMemoryFetcher::new()
+            .with_body("http://example.org", "hello")
+            .with_response("http://example.org/gone/", FetchResponse::new(410, ""));
+
+        // "http://example.org" is normalized to "http://example.org/"
+        assert_eq!(fetch_text(&fetcher, "http://example.org/").await.ok(), Some("hello".to_string()));
+
+        match fetch_text(&fetcher, "http://example.org/gone/").await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(e.to_string(), "Error retrieving content from 'http://example.org/gone/': 410 Gone"),
+        }
+
+        match fetch_text(&fetcher, "http://example.org/missing").await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => {
+                assert_eq!(e.to_string(), "Error retrieving content from 'http://example.org/missing': 404 Not Found");
+            }
+        }
+    }
+}
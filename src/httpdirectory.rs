@@ -1,29 +1,56 @@
 use crate::{
-    error::{ParseResultExt, RegexResultExt, ReqwestResultExt, Result},
+    checksum::{ChecksumAlgorithm, ChecksumManifest},
+    conditional::ListingCache,
+    detect::SiteType,
+    diff::{ListingDiff, diff},
+    download::{Progress, download},
+    error::{HttpDirError, ParseResultExt, RegexResultExt, Result},
+    fetcher::{FetchResponse, Fetcher, fetch_text, status_error},
+    httpdirectorybuilder::HttpDirectoryBuilder,
     httpdirectoryentry::{CompareField, HttpDirectoryEntry},
+    parseoptions::ParseOptions,
     requests::Request,
-    scrape::scrape_body,
+    scrapers::ScraperRegistry,
     stats::Stats,
+    walk::{WalkOptions, walk},
+    watch::{WatchEvent, watch},
 };
+use futures::stream::BoxStream;
 use regex::Regex;
 use reqwest::Url;
+use std::collections::HashSet;
 use std::fmt;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{debug, error, trace};
+use tracing::{debug, error, info, warn};
+use unwrap_unreachable::UnwrapUnreachable;
 
 /// Main structure that provides methods to access, parse a directory
-/// webpage and fill that structure.
+/// webpage and fill that structure. With the `serde` feature the fetcher
+/// (HTTP client), the scrapers, the parse options and the validators
+/// remembered for conditional requests are not serialized: default
+/// ones are used when deserializing.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HttpDirectory {
     entries: Vec<HttpDirectoryEntry>,
     url: Arc<String>,
-    request: Arc<Request>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_fetcher"))]
+    fetcher: Arc<dyn Fetcher>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrapers: Arc<ScraperRegistry>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: Arc<ParseOptions>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    listings: Arc<ListingCache>,
+    site_type: Option<SiteType>,
     timings: Arc<Timings>,
 }
 
 #[derive(Debug, Default, Clone)]
-struct Timings {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Timings {
     /// Total time passed processing the http request
     http_request: Duration,
     get_entries: Duration,
@@ -43,7 +70,9 @@ impl HttpDirectory {
     /// Crawls the `url` and returns (if no error occurred) the
     /// `HttpDirectory` of that url. `timeout_s` optionally defines
     /// a global request timeout in seconds. `None` is no timeout
-    /// at all.
+    /// at all. 502, 503 and 504 HTTP status codes and timeouts are
+    /// retried (3 attempts at most): use `HttpDirectoryBuilder` with
+    /// another `RetryPolicy` to change this.
     ///
     /// # Errors
     ///
@@ -52,21 +81,112 @@ impl HttpDirectory {
     /// with a 200 HTTP status code
     #[cfg_attr(feature = "hotpath", hotpath::measure)]
     pub async fn new(url: &str, timeout_s: Option<u64>) -> Result<Self> {
-        let now = Instant::now();
-        let client = Request::new(timeout_s)?;
-        let response = client.get(url).await?;
-        let http_request = now.elapsed();
-        trace!("Response to get '{url}': {response:?}");
+        let mut builder = HttpDirectoryBuilder::new();
+        if let Some(timeout_s) = timeout_s {
+            builder = builder.timeout(Duration::from_secs(timeout_s));
+        }
+        builder.build(url).await
+    }
+
+    /// Crawls the `url` with `fetcher` instead of the default HTTP
+    /// client and returns (if no error occurred) the `HttpDirectory`
+    /// of that url. The fetcher is kept for later `cd()` calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` could not be fetched or that the
+    /// fetcher did not return a 200 HTTP status code
+    pub async fn with_fetcher<F: Fetcher + 'static>(url: &str, fetcher: F) -> Result<Self> {
+        HttpDirectory::from_fetcher(
+            url,
+            Arc::new(fetcher),
+            ScraperRegistry::default(),
+            ParseOptions::default(),
+            Arc::default(),
+        )
+        .await
+    }
+
+    /// Scrapes `body` that is the HTML listing of `base_url` without
+    /// any network access and returns the `HttpDirectory` of that url.
+    /// `cd()` may then be used relative to `base_url`.
+    ///
+    /// ```rust
+    ///   use httpdirectory::httpdirectory::HttpDirectory;
+    ///   let body = "<ul>\n<li><a href=\"dir/\">dir/</a></li>\n<li><a href=\"file\">file</a></li>\n</ul>";
+    ///   if let Ok(httpdir) = HttpDirectory::from_html("https://example.org/pub/", body) {
+    ///       assert_eq!(httpdir.dirs().len(), 1);
+    ///       assert_eq!(httpdir.files().len(), 1);
+    ///   }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base_url` is not a valid url
+    pub fn from_html(base_url: &str, body: &str) -> Result<Self> {
+        HttpDirectory::from_body(
+            base_url,
+            body,
+            default_fetcher(),
+            ScraperRegistry::default(),
+            ParseOptions::default(),
+            Arc::default(),
+        )
+    }
+
+    /// Scrapes `body` of `url` with `scrapers` and `options`. The
+    /// fetcher, the scrapers, the options and the listing cache are
+    /// kept for later `cd()` calls.
+    pub(crate) fn from_body(
+        url: &str,
+        body: &str,
+        fetcher: Arc<dyn Fetcher>,
+        scrapers: ScraperRegistry,
+        options: ParseOptions,
+        listings: Arc<ListingCache>,
+    ) -> Result<Self> {
+        let base = Url::parse(url).with_url(url)?;
 
         let now = Instant::now();
-        let entries = get_entries_from_body(&response.text().await.with_url(url)?);
-        let get_entries = now.elapsed();
-        let timings = Timings::new(http_request, get_entries);
+        let (mut entries, site_type) = entries_from_body(&scrapers, &options, body, None);
+        resolve_urls(&scrapers, site_type.as_ref(), &mut entries, &base);
+        let timings = Timings::new(Duration::ZERO, now.elapsed());
+
+        Ok(HttpDirectory {
+            entries,
+            site_type,
+            url: Arc::new(url.to_string()),
+            fetcher,
+            scrapers: Arc::new(scrapers),
+            options: Arc::new(options),
+            listings,
+            timings: Arc::new(timings),
+        })
+    }
+
+    /// Crawls the `url` with `fetcher` and scrapes it with
+    /// `scrapers` and `options`. Returns (if no error occurred)
+    /// the `HttpDirectory` of that url. The fetcher, the scrapers,
+    /// the options and the listing cache are kept for later `cd()`
+    /// calls.
+    pub(crate) async fn from_fetcher(
+        url: &str,
+        fetcher: Arc<dyn Fetcher>,
+        scrapers: ScraperRegistry,
+        options: ParseOptions,
+        listings: Arc<ListingCache>,
+    ) -> Result<Self> {
+        let (entries, site_type, timings) =
+            fetch_entries(fetcher.as_ref(), &scrapers, &options, &listings, url).await?;
 
         Ok(HttpDirectory {
             entries,
+            site_type,
             url: Arc::new(url.to_string()),
-            request: Arc::new(client),
+            fetcher,
+            scrapers: Arc::new(scrapers),
+            options: Arc::new(options),
+            listings,
             timings: Arc::new(timings),
         })
     }
@@ -82,24 +202,180 @@ impl HttpDirectory {
     /// - the web server did not respond with 200 HTTP status code
     #[cfg_attr(feature = "hotpath", hotpath::measure)]
     pub async fn cd(mut self, dir: &str) -> Result<Self> {
-        let url =
-            Url::parse(&self.url).with_url(&self.url)?.join(dir).with_url(&format!("{}/{dir}", &self.url))?.to_string();
+        let base = Url::parse(&self.url).with_url(&self.url)?;
+        // Some listings (S3 buckets) know better than joining
+        // `dir` to their url where the listing of `dir` is
+        let url = match self
+            .site_type
+            .as_ref()
+            .and_then(|site_type| self.scrapers.get(site_type.name()))
+            .and_then(|scraper| scraper.directory_url(&base, dir))
+        {
+            Some(url) => url.to_string(),
+            None => base.join(dir).with_url(&format!("{}/{dir}", &self.url))?.to_string(),
+        };
         debug!("cd is going to {url}");
-        let now = Instant::now();
-        let response = self.request.get(&url).await?;
-        let http_request = now.elapsed();
+        let (entries, site_type, timings) =
+            fetch_entries(self.fetcher.as_ref(), &self.scrapers, &self.options, &self.listings, &url).await?;
 
-        let now = Instant::now();
-        let entries = get_entries_from_body(&response.text().await.with_url(&self.url)?);
-        let get_entries = now.elapsed();
-
-        let timings = Timings::new(http_request, get_entries);
         self.entries = entries;
+        self.site_type = site_type;
         self.timings = Arc::new(timings);
         self.url = Arc::new(url);
         Ok(self)
     }
 
+    /// Retrieves again the listing of this `HttpDirectory` (same url,
+    /// same client, scrapers and options) and returns it as a new `HttpDirectory`
+    /// that may be compared to this one with `diff()`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    /// - an error occurred while trying to retrieve data from the directory
+    /// - the web server did not respond with 200 HTTP status code
+    pub async fn refresh(&self) -> Result<Self> {
+        let (entries, site_type, timings) =
+            fetch_entries(self.fetcher.as_ref(), &self.scrapers, &self.options, &self.listings, &self.url).await?;
+
+        Ok(HttpDirectory {
+            entries,
+            url: Arc::clone(&self.url),
+            fetcher: Arc::clone(&self.fetcher),
+            scrapers: Arc::clone(&self.scrapers),
+            options: Arc::clone(&self.options),
+            listings: Arc::clone(&self.listings),
+            site_type,
+            timings: Arc::new(timings),
+        })
+    }
+
+    /// Returns the differences between this listing (the old one)
+    /// and `newer`: added, removed and modified (date and/or size)
+    /// entries matched by name. `newer` is usually the result of
+    /// `refresh()` and this listing may be a deserialized snapshot.
+    #[must_use]
+    pub fn diff(&self, newer: &HttpDirectory) -> ListingDiff {
+        diff(&self.entries, &newer.entries)
+    }
+
+    /// Walks recursively the index tree below this `HttpDirectory`
+    /// and returns a stream of `(path, HttpDirectoryEntry)` where
+    /// path is relative to this directory (directories end with
+    /// '/'). Parent directories are never returned. Walking is
+    /// bounded by `options` (see [`WalkOptions`]) and each url is
+    /// visited only once. An error while retrieving a directory
+    /// is returned as an item and does not stop the walk.
+    #[must_use]
+    pub fn walk(&self, options: WalkOptions) -> BoxStream<'static, Result<(String, HttpDirectoryEntry)>> {
+        walk(
+            Arc::clone(&self.fetcher),
+            Arc::clone(&self.scrapers),
+            Arc::clone(&self.options),
+            Arc::clone(&self.listings),
+            &self.url,
+            self.entries.clone(),
+            options,
+        )
+    }
+
+    /// Downloads the file `entry` (usually one of the entries of
+    /// this `HttpDirectory`) to `path` with the same client used
+    /// to retrieve the listing and returns the length of the file.
+    /// The file is streamed to disk. A partial file already at
+    /// `path` is resumed with a `Range` request (it is downloaded
+    /// again from its beginning if the server does not support
+    /// ranges).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    /// - `entry` is not a file or its url is not known
+    /// - the web server did not respond with 200 or 206 HTTP status code
+    /// - the file could not be written to `path`
+    /// - the length of the file is not the exact size given by the
+    ///   listing (when it is given in bytes) or by the server
+    pub async fn download(&self, entry: &HttpDirectoryEntry, path: impl AsRef<Path>) -> Result<u64> {
+        self.download_with_progress(entry, path, |_| ()).await
+    }
+
+    /// Same as `download()` but `progress` is called with the
+    /// [`Progress`] of the download after each chunk written to disk
+    ///
+    /// # Errors
+    ///
+    /// Will return the same errors than `download()`
+    pub async fn download_with_progress<F>(
+        &self,
+        entry: &HttpDirectoryEntry,
+        path: impl AsRef<Path>,
+        progress: F,
+    ) -> Result<u64>
+    where
+        F: FnMut(Progress),
+    {
+        download(self.fetcher.as_ref(), entry, path.as_ref(), progress).await
+    }
+
+    /// Retrieves and parses the checksum manifests found among the
+    /// files of this `HttpDirectory` (see `checksum_manifests()`) and
+    /// attaches the expected digest to each file listed in one of
+    /// them. When a file is listed in several manifests the digest
+    /// of the strongest algorithm is kept. Use
+    /// `HttpDirectoryEntry::verify()` to check a downloaded file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a manifest could not be retrieved
+    pub async fn load_checksums(mut self) -> Result<Self> {
+        let mut manifests = vec![];
+        for entry in &self.entries {
+            if let (Some(algorithm), Some(url)) =
+                (entry.filename().and_then(ChecksumAlgorithm::from_manifest_name), entry.url())
+            {
+                let body = fetch_text(self.fetcher.as_ref(), url.as_str()).await?;
+                let manifest = ChecksumManifest::parse(algorithm, &body);
+                debug!("{algorithm} manifest {url} lists {} files", manifest.len());
+                manifests.push(manifest);
+            }
+        }
+
+        // Stronger algorithms are applied last and win
+        manifests.sort_by_key(ChecksumManifest::algorithm);
+        for manifest in &manifests {
+            for entry in &mut self.entries {
+                if let Some(checksum) = entry.filename().and_then(|name| manifest.get(name)) {
+                    entry.set_checksum(checksum);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Polls the listing of this `HttpDirectory` every `interval` and
+    /// returns a never ending stream of [`WatchEvent`] (added, removed
+    /// or modified entries) compared to the previous listing. Polls use
+    /// conditional requests (`If-None-Match` and `If-Modified-Since`),
+    /// unless disabled with `HttpDirectoryBuilder::conditional_requests()`,
+    /// so an unchanged listing only costs a 304 HTTP status code. An
+    /// error while polling is returned as an item and does not stop
+    /// the watch. With a `DiskCache` polls are served from the cache
+    /// while the listing is fresh: changes are only seen once it is
+    /// older than the cache's time to live.
+    #[must_use]
+    pub fn watch(&self, interval: Duration) -> BoxStream<'static, Result<WatchEvent>> {
+        watch(
+            Arc::clone(&self.fetcher),
+            Arc::clone(&self.scrapers),
+            Arc::clone(&self.options),
+            Arc::clone(&self.listings),
+            &self.url,
+            self.entries.clone(),
+            interval,
+        )
+    }
+
     /// Sorts the Directory entries by their names in ascending order when
     /// `ascending` is `true`, in descending order otherwise
     #[must_use]
@@ -142,7 +418,11 @@ impl HttpDirectory {
         HttpDirectory {
             entries,
             url: Arc::clone(&self.url),
-            request: Arc::clone(&self.request),
+            fetcher: Arc::clone(&self.fetcher),
+            scrapers: Arc::clone(&self.scrapers),
+            options: Arc::clone(&self.options),
+            listings: Arc::clone(&self.listings),
+            site_type: self.site_type.clone(),
             timings: Arc::clone(&self.timings),
         }
     }
@@ -165,6 +445,13 @@ impl HttpDirectory {
         self.filtering(HttpDirectoryEntry::is_parent_directory)
     }
 
+    /// Returns only the checksum manifests (`SHA256SUMS`, `SHA512SUMS`
+    /// or `MD5SUMS` files) of the `HttpDirectory` listing
+    #[must_use]
+    pub fn checksum_manifests(&self) -> Self {
+        self.filtering(|entry| entry.filename().and_then(ChecksumAlgorithm::from_manifest_name).is_some())
+    }
+
     /// Returns the last entry Some(`HttpDirectoryEntry`) of that `HttpDirectory`
     /// if it exists or None
     #[must_use]
@@ -227,6 +514,14 @@ impl HttpDirectory {
         &self.entries
     }
 
+    /// Returns the listing format detected when scraping this
+    /// `HttpDirectory` (with the version of the listing software
+    /// if known) or `None` if no format has been recognized
+    #[must_use]
+    pub fn site_type(&self) -> Option<&SiteType> {
+        self.site_type.as_ref()
+    }
+
     /// Returns the String that represents the url of
     /// that `HttpDirectory`
     #[must_use]
@@ -275,40 +570,201 @@ impl fmt::Display for HttpDirectory {
 
 impl Default for HttpDirectory {
     /// Returns an `HttpDirectory` initialized with default
-    /// values (empty vector, empty url and defaults Request
-    /// and Timings)
+    /// values (empty vector, empty url, no site type and defaults
+    /// Request, scrapers, parse options and Timings)
     fn default() -> Self {
         HttpDirectory {
             entries: vec![],
             url: Arc::new(String::new()),
-            request: Arc::new(Request::default()),
+            fetcher: default_fetcher(),
+            scrapers: Arc::new(ScraperRegistry::default()),
+            options: Arc::new(ParseOptions::default()),
+            listings: Arc::default(),
+            site_type: None,
             timings: Arc::new(Timings::default()),
         }
     }
 }
 
-fn entries_from_body(body: &str) -> Vec<HttpDirectoryEntry> {
-    match scrape_body(body) {
-        Ok(entries) => entries,
+// Default HTTP client used as a `Fetcher`: the one a default
+// `HttpDirectoryBuilder` builds (user agent, gzip, retries...).
+// Building it only fails with invalid headers and there are none
+fn default_fetcher() -> Arc<dyn Fetcher> {
+    Arc::new(Request::new(&HttpDirectoryBuilder::default()).unreachable())
+}
+
+// Retrieves the body of `url` with `fetcher` and scrapes it
+// with `scrapers` and `options`, timing both operations. A conditional
+// request is sent when `listings` knows the validators of
+// `url` and the cached entries are reused if the server
+// answers with a 304 HTTP status code.
+pub(crate) async fn fetch_entries(
+    fetcher: &dyn Fetcher,
+    scrapers: &ScraperRegistry,
+    options: &ParseOptions,
+    listings: &ListingCache,
+    url: &str,
+) -> Result<(Vec<HttpDirectoryEntry>, Option<SiteType>, Timings)> {
+    Url::parse(url).with_url(url)?;
+
+    let now = Instant::now();
+    let response = fetcher.fetch_with_headers(url, &listings.conditional_headers(url)).await?;
+    let http_request = now.elapsed();
+
+    if response.status == 304
+        && let Some(listing) = listings.get(url)
+    {
+        debug!("{url} has not been modified: reusing its entries");
+        return Ok((listing.entries, listing.site_type, Timings::new(http_request, Duration::ZERO)));
+    } else if response.status != 200 {
+        error!("Error while retrieving url {url} content: {}", response.status);
+        return Err(status_error(url, response.status));
+    }
+
+    let now = Instant::now();
+    let (mut entries, site_type) = scrape_entries(scrapers, options, url, &response);
+    let get_entries = now.elapsed();
+    fetch_next_pages(fetcher, scrapers, options, site_type.as_ref(), url, &response.body, &mut entries).await?;
+    listings.store(url, &response, &entries, site_type.as_ref());
+
+    Ok((entries, site_type, Timings::new(http_request, get_entries)))
+}
+
+// Retrieves the next pages of `body`, the first page of the
+// listing of `url`, when the scraper of `site_type` tells that
+// the listing is paginated (S3 buckets) and appends their entries
+// to `entries`. A directory already listed by a previous page
+// is not listed again. Fails when a page already retrieved is
+// given again as the next one (the token or marker of each page
+// is in its url) or when the listing has more pages than the
+// maximum set in `options`.
+async fn fetch_next_pages(
+    fetcher: &dyn Fetcher,
+    scrapers: &ScraperRegistry,
+    options: &ParseOptions,
+    site_type: Option<&SiteType>,
+    url: &str,
+    body: &str,
+    entries: &mut Vec<HttpDirectoryEntry>,
+) -> Result<()> {
+    let Some(scraper) = site_type.and_then(|site_type| scrapers.get(site_type.name())) else {
+        return Ok(());
+    };
+    let page_url = Url::parse(url).with_url(url)?;
+    let mut next = scraper.next_page_url(&page_url, body);
+    let mut pages = HashSet::from([page_url]);
+    let mut directories: HashSet<String> =
+        entries.iter().filter_map(|entry| entry.dirname().map(str::to_string)).collect();
+
+    while let Some(next_url) = next.take() {
+        if pages.len() >= options.page_limit() {
+            error!("{url} has more than {} pages", options.page_limit());
+            return Err(HttpDirError::TooManyPages {
+                url: url.to_string(),
+                max_pages: options.page_limit(),
+            });
+        }
+        if !pages.insert(next_url.clone()) {
+            error!("{url} loops back to {next_url}");
+            return Err(HttpDirError::PaginationLoop {
+                url: url.to_string(),
+                page: next_url.to_string(),
+            });
+        }
+
+        debug!("{url} continues at {next_url}");
+        let response = fetcher.fetch(next_url.as_str()).await?;
+        if response.status != 200 {
+            error!("Error while retrieving url {next_url} content: {}", response.status);
+            return Err(status_error(next_url.as_str(), response.status));
+        }
+
+        let mut page = scraper.scrape(&response.body)?;
+        options.complete_entries(scraper, &mut page);
+        resolve_urls(scrapers, site_type, &mut page, &next_url);
+        for entry in page {
+            match entry.dirname() {
+                Some(dirname) if !directories.insert(dirname.to_string()) => (),
+                _ => entries.push(entry),
+            }
+        }
+
+        next = scraper.next_page_url(&next_url, &response.body);
+    }
+
+    Ok(())
+}
+
+// Scrapes the body of `response`, the listing of `url`, with
+// `scrapers` and `options` and resolves the url of each entry found
+fn scrape_entries(
+    scrapers: &ScraperRegistry,
+    options: &ParseOptions,
+    url: &str,
+    response: &FetchResponse,
+) -> (Vec<HttpDirectoryEntry>, Option<SiteType>) {
+    let (mut entries, site_type) =
+        entries_from_body(scrapers, options, &response.body, response.header("Content-Type"));
+    if let Ok(url) = Url::parse(url) {
+        resolve_urls(scrapers, site_type.as_ref(), &mut entries, &url);
+    }
+    (entries, site_type)
+}
+
+// Resolves the url of each entry against the base url of `url`,
+// the url of the listing, given by the scraper of `site_type`
+// (`url` itself unless the scraper knows better)
+fn resolve_urls(
+    scrapers: &ScraperRegistry,
+    site_type: Option<&SiteType>,
+    entries: &mut [HttpDirectoryEntry],
+    url: &Url,
+) {
+    let base = match site_type.and_then(|site_type| scrapers.get(site_type.name())) {
+        Some(scraper) => scraper.base_url(url),
+        None => url.clone(),
+    };
+    for entry in entries {
+        entry.resolve_url(&base);
+    }
+}
+
+// Scrapes `body` (served with `content_type` if known) with the
+// scraper of `scrapers` that recognizes it best, completes them
+// with `options` and returns the entries found and the detected
+// site type
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+fn entries_from_body(
+    scrapers: &ScraperRegistry,
+    options: &ParseOptions,
+    body: &str,
+    content_type: Option<&str>,
+) -> (Vec<HttpDirectoryEntry>, Option<SiteType>) {
+    let Some(scraper) = scrapers.detect_with_content_type(body, content_type) else {
+        warn!("Site type has not been detected: doing nothing");
+        return (vec![], None);
+    };
+
+    let site_type = SiteType::from_scraper(scraper, body);
+    info!("{site_type} listing detected");
+    match scraper.scrape(body) {
+        Ok(mut entries) => {
+            options.complete_entries(scraper, &mut entries);
+            (entries, Some(site_type))
+        }
         Err(e) => {
             error!("Error getting entries: {e}");
-            vec![]
+            (vec![], Some(site_type))
         }
     }
 }
 
-/// feature gated to be used only in tests - this method
-/// should not be public
+/// feature gated to be used only in tests and benches: scrapes
+/// `body` with the built-in scrapers. Use `HttpDirectory::from_html()`
+/// otherwise.
 #[cfg(any(test, feature = "test-helpers"))]
 pub fn get_entries_from_body(body: &str) -> Vec<HttpDirectoryEntry> {
-    entries_from_body(body)
-}
-
-/// feature gated for production use
-#[cfg(not(any(test, feature = "test-helpers")))]
-#[cfg_attr(feature = "hotpath", hotpath::measure)]
-pub(crate) fn get_entries_from_body(body: &str) -> Vec<HttpDirectoryEntry> {
-    entries_from_body(body)
+    entries_from_body(ScraperRegistry::builtin(), &ParseOptions::default(), body, None).0
 }
 
 #[cfg(test)]
@@ -316,12 +772,70 @@ mod tests {
     use {
         super::{HttpDirectory, HttpDirectoryEntry},
         crate::{
+            fetcher::InMemoryFetcher,
+            httpdirectorybuilder::HttpDirectoryBuilder,
             httpdirectoryentry::{EntryType, assert_entry},
             stats::Stats,
         },
         unwrap_unreachable::UnwrapUnreachable,
     };
 
+    // Page of a bucket listing with one `key`, continued by `token` if any
+    fn s3_page(key: &str, token: Option<&str>) -> String {
+        let next = token.map_or_else(
+            || "<IsTruncated>false</IsTruncated>".to_string(),
+            |token| format!("<IsTruncated>true</IsTruncated><NextContinuationToken>{token}</NextContinuationToken>"),
+        );
+        format!(
+            "<ListBucketResult><Prefix></Prefix>{next}<Contents><Key>{key}</Key><LastModified>2025-05-31T13:54:45Z</LastModified><Size>1</Size></Contents></ListBucketResult>"
+        )
+    }
+
+    // Bucket whose first page is continued by `a`, `a` by `b` and `b` by `next_of_b`
+    fn s3_fetcher(next_of_b: Option<&str>) -> InMemoryFetcher {
+        InMemoryFetcher::new()
+            .with_body("http://bucket.example.org/?list-type=2", &s3_page("one", Some("a")))
+            .with_body("http://bucket.example.org/?list-type=2&continuation-token=a", &s3_page("two", Some("b")))
+            .with_body("http://bucket.example.org/?list-type=2&continuation-token=b", &s3_page("three", next_of_b))
+    }
+
+    #[tokio::test]
+    async fn test_httpdirectory_pages() {
+        let httpdir = HttpDirectoryBuilder::new()
+            .fetcher(s3_fetcher(None))
+            .build("http://bucket.example.org/?list-type=2")
+            .await
+            .unreachable();
+        let names: Vec<_> = httpdir.entries().iter().filter_map(HttpDirectoryEntry::name).collect();
+        assert_eq!(names, vec!["one", "two", "three"]);
+    }
+
+    #[tokio::test]
+    async fn test_httpdirectory_pages_loop() {
+        match HttpDirectoryBuilder::new()
+            .fetcher(s3_fetcher(Some("a")))
+            .build("http://bucket.example.org/?list-type=2")
+            .await
+        {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Listing of 'http://bucket.example.org/?list-type=2' loops back to page 'http://bucket.example.org/?list-type=2&continuation-token=a'"
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_httpdirectory_too_many_pages() {
+        let builder = HttpDirectoryBuilder::new().fetcher(s3_fetcher(None)).max_pages(2);
+        match builder.build("http://bucket.example.org/?list-type=2").await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => {
+                assert_eq!(e.to_string(), "Listing of 'http://bucket.example.org/?list-type=2' has more than 2 pages");
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_httpdirectory_no_base_url() {
         let httpdir = HttpDirectory::default();
@@ -752,4 +1266,49 @@ Entries without any date: 0
         assert_eq!(stats.with_date, 0);
         assert_eq!(stats.without_date, 2);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_httpdirectory_serde_round_trip() {
+        let httpdir = prepare_httpdir();
+
+        let json = serde_json::to_string(&httpdir).unreachable();
+        let back: HttpDirectory = serde_json::from_str(&json).unreachable();
+
+        assert_eq!(back.get_url(), httpdir.get_url());
+        assert_eq!(format!("{:?}", back.entries()), format!("{:?}", httpdir.entries()));
+        assert_eq!(back.total_time(), httpdir.total_time());
+        assert_eq!(serde_json::to_string(&back).unreachable(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_stats_serde_round_trip() {
+        let stats = prepare_httpdir().stats();
+
+        let json = serde_json::to_string(&stats).unreachable();
+        assert_eq!(
+            json,
+            r#"{"parent_dir":1,"dirs":4,"files":4,"total_size":129045924,"with_date":8,"without_date":1}"#
+        );
+
+        let back: Stats = serde_json::from_str(&json).unreachable();
+        assert_eq!(format!("{back:?}"), format!("{stats:?}"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_diff_with_deserialized_snapshot() {
+        let snapshot: HttpDirectory =
+            serde_json::from_str(&serde_json::to_string(&prepare_httpdir()).unreachable()).unreachable();
+
+        let mut fresh = prepare_httpdir();
+        fresh.entries.retain(|entry| entry.name() != Some("file1"));
+        fresh.entries.push(HttpDirectoryEntry::new("file5", "2025-08-01 10:00", "42", "file5"));
+
+        let listing_diff = snapshot.diff(&fresh);
+        assert_eq!(listing_diff.added.iter().filter_map(HttpDirectoryEntry::name).collect::<Vec<_>>(), vec!["file5"]);
+        assert_eq!(listing_diff.removed.iter().filter_map(HttpDirectoryEntry::name).collect::<Vec<_>>(), vec!["file1"]);
+        assert!(listing_diff.modified.is_empty());
+    }
 }
@@ -0,0 +1,423 @@
+use crate::{
+    HTTPDIR_USER_AGENT,
+    cache::DiskCache,
+    conditional::ListingCache,
+    date::MonthNames,
+    error::Result,
+    fetcher::Fetcher,
+    httpdirectory::HttpDirectory,
+    parseoptions::ParseOptions,
+    requests::Request,
+    retry::RetryPolicy,
+    scrapers::{Scraper, ScraperRegistry},
+};
+use chrono::FixedOffset;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Redirect policy used by the underlying HTTP client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    /// Never follow any redirection
+    None,
+
+    /// Follows at most this number of redirections
+    Limited(usize),
+}
+
+impl Default for RedirectPolicy {
+    /// Follows up to 10 redirections (the same default as reqwest)
+    fn default() -> Self {
+        RedirectPolicy::Limited(10)
+    }
+}
+
+/// Builder that configures the HTTP client used to retrieve
+/// directory listings and produces an `HttpDirectory`. The same
+/// client is kept by the `HttpDirectory` for later `cd()` calls.
+///
+/// ```rust
+///   use httpdirectory::httpdirectorybuilder::HttpDirectoryBuilder;
+///   use std::time::Duration;
+///   async fn builder_example() {
+///     let builder = HttpDirectoryBuilder::new()
+///         .user_agent("mirror-checker/1.0")
+///         .connect_timeout(Duration::from_secs(5))
+///         .read_timeout(Duration::from_secs(30));
+///     if let Ok(httpdir) = builder.build("https://cloud.debian.org/images/cloud/").await {
+///         println!("{:?}", httpdir.dirs());
+///     }
+///   }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpDirectoryBuilder {
+    pub(crate) user_agent: String,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) redirect: RedirectPolicy,
+    pub(crate) max_body_size: Option<u64>,
+    pub(crate) http1_only: bool,
+    pub(crate) gzip: bool,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) disk_cache: Option<DiskCache>,
+    pub(crate) scrapers: ScraperRegistry,
+    pub(crate) options: ParseOptions,
+    pub(crate) fetcher: Option<Arc<dyn Fetcher>>,
+    pub(crate) listings: Arc<ListingCache>,
+}
+
+impl Default for HttpDirectoryBuilder {
+    /// Returns a builder with httpdirectory's user agent, no
+    /// timeouts, no default headers, at most 10 redirections,
+    /// no body size limit, HTTP/2 allowed, gzip enabled, the
+    /// `RetryPolicy::default()` retry policy, no disk cache,
+    /// built-in scrapers and date formats only, dates in UTC,
+    /// at most `DEFAULT_MAX_PAGES` pages per listing, reqwest
+    /// as fetcher and conditional requests enabled
+    fn default() -> Self {
+        HttpDirectoryBuilder {
+            user_agent: HTTPDIR_USER_AGENT.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            read_timeout: None,
+            headers: vec![],
+            redirect: RedirectPolicy::default(),
+            max_body_size: None,
+            http1_only: false,
+            gzip: true,
+            retry: RetryPolicy::default(),
+            disk_cache: None,
+            scrapers: ScraperRegistry::default(),
+            options: ParseOptions::default(),
+            fetcher: None,
+            listings: Arc::default(),
+        }
+    }
+}
+
+impl HttpDirectoryBuilder {
+    /// Creates a new builder with default values
+    #[must_use]
+    pub fn new() -> Self {
+        HttpDirectoryBuilder::default()
+    }
+
+    /// Sets the user agent sent with each request (defaults
+    /// to `HTTPDIR_USER_AGENT`)
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Sets a global timeout for each request: from the
+    /// connection until the end of the response body
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a timeout for the connect phase only
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a timeout applied to each read operation. It is
+    /// reset after each successful read.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header that will be sent with each request. The
+    /// name and value are validated when calling `build()`
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the redirect policy (defaults to 10 redirections)
+    #[must_use]
+    pub fn redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect = policy;
+        self
+    }
+
+    /// Sets the maximum size in bytes of a response body. A
+    /// bigger body makes the request fail.
+    #[must_use]
+    pub fn max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Only uses HTTP/1 when `http1_only` is `true`
+    #[must_use]
+    pub fn http1_only(mut self, http1_only: bool) -> Self {
+        self.http1_only = http1_only;
+        self
+    }
+
+    /// Enables or disables gzip compressed responses
+    #[must_use]
+    pub fn gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Sets the retry policy applied to every request made by
+    /// the `HttpDirectory` (including `cd()` and `walk()`).
+    /// Defaults to `RetryPolicy::default()`: use
+    /// `RetryPolicy::none()` to never retry
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Stores the listings retrieved by the HTTP client in `cache`
+    /// and serves them from it while they are fresh (or always in
+    /// offline mode, see [`DiskCache`])
+    #[must_use]
+    pub fn disk_cache(mut self, cache: DiskCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// Registers a user `scraper` that is tried before the
+    /// built-in ones (see [`ScraperRegistry`])
+    #[must_use]
+    pub fn scraper<S: Scraper + 'static>(mut self, scraper: S) -> Self {
+        self.scrapers.register(scraper);
+        self
+    }
+
+    /// Sets the timezone of the dates printed without any offset
+    /// by listings (UTC by default): `HttpDirectoryEntry::date()`
+    /// then returns them with this offset. Dates printed with an
+    /// offset or a known timezone abbreviation keep their own.
+    #[must_use]
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.options = self.options.timezone(timezone);
+        self
+    }
+
+    /// Adds a `strftime` like date `format` tried after the built-in
+    /// ones on the dates of listings that they do not understand
+    #[must_use]
+    pub fn date_format(mut self, format: &str) -> Self {
+        self.options = self.options.date_format(format);
+        self
+    }
+
+    /// Adds the month names of a language (see [`MonthNames`]) used
+    /// to read dates such as `05-Avr-2024 11:59` after the built-in
+    /// formats failed
+    #[must_use]
+    pub fn month_names(mut self, month_names: MonthNames) -> Self {
+        self.options = self.options.month_names(month_names);
+        self
+    }
+
+    /// Sets the maximum number of pages (at least 1) retrieved for a
+    /// paginated listing such as an S3 bucket (`DEFAULT_MAX_PAGES` by
+    /// default): retrieving a listing with more pages fails
+    #[must_use]
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.options = self.options.max_pages(max_pages);
+        self
+    }
+
+    /// Uses `fetcher` to retrieve listings instead of the HTTP
+    /// client configured by this builder: client settings (user
+    /// agent, timeouts, retry policy, ...) are then ignored
+    #[must_use]
+    pub fn fetcher<F: Fetcher + 'static>(mut self, fetcher: F) -> Self {
+        self.fetcher = Some(Arc::new(fetcher));
+        self
+    }
+
+    /// Enables (default) or disables conditional requests. When
+    /// enabled the `ETag` and `Last-Modified` headers of each listing
+    /// retrieved are remembered (by this builder and the directories
+    /// it builds) and sent back with `If-None-Match` and
+    /// `If-Modified-Since` headers: a 304 HTTP status code then reuses
+    /// the entries previously parsed instead of failing.
+    #[must_use]
+    pub fn conditional_requests(mut self, enabled: bool) -> Self {
+        self.listings = Arc::new(ListingCache::new(enabled));
+        self
+    }
+
+    /// Crawls the `url` with a client configured from this builder
+    /// and returns (if no error occurred) the `HttpDirectory` of
+    /// that url.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request client could not be made
+    /// (invalid header for instance) or that the request to the
+    /// url did not return correctly with a 200 HTTP status code
+    pub async fn build(&self, url: &str) -> Result<HttpDirectory> {
+        HttpDirectory::from_fetcher(
+            url,
+            self.build_fetcher()?,
+            self.scrapers.clone(),
+            self.options.clone(),
+            Arc::clone(&self.listings),
+        )
+        .await
+    }
+
+    /// Scrapes `body` that is the HTML listing of `base_url` without
+    /// any network access and returns its `HttpDirectory`. Later
+    /// `cd()` calls use the client configured by this builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request client could not be made or
+    /// if `base_url` is not a valid url
+    pub fn build_from_html(&self, base_url: &str, body: &str) -> Result<HttpDirectory> {
+        HttpDirectory::from_body(
+            base_url,
+            body,
+            self.build_fetcher()?,
+            self.scrapers.clone(),
+            self.options.clone(),
+            Arc::clone(&self.listings),
+        )
+    }
+
+    // Returns the user's fetcher if any or a new client
+    // configured with this builder
+    fn build_fetcher(&self) -> Result<Arc<dyn Fetcher>> {
+        match &self.fetcher {
+            Some(fetcher) => Ok(Arc::clone(fetcher)),
+            None => Ok(Arc::new(Request::new(self)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpDirectoryBuilder, RedirectPolicy, RetryPolicy};
+    use crate::{date::MonthNames, detect::SiteType, fetcher::InMemoryFetcher};
+    use chrono::FixedOffset;
+    use std::time::Duration;
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    #[test]
+    fn test_builder_default() {
+        let builder = HttpDirectoryBuilder::new();
+
+        assert_eq!(builder.user_agent, crate::HTTPDIR_USER_AGENT);
+        assert_eq!(builder.timeout, None);
+        assert_eq!(builder.redirect, RedirectPolicy::Limited(10));
+        assert_eq!(builder.max_body_size, None);
+        assert!(!builder.http1_only);
+        assert!(builder.gzip);
+        assert_eq!(builder.retry, RetryPolicy::default());
+    }
+
+    #[test]
+    fn test_builder_settings() {
+        let builder = HttpDirectoryBuilder::new()
+            .user_agent("mirror-checker/1.0")
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(10))
+            .header("X-Token", "secret")
+            .redirect_policy(RedirectPolicy::None)
+            .max_body_size(1_024)
+            .http1_only(true)
+            .gzip(false);
+
+        assert_eq!(builder.user_agent, "mirror-checker/1.0");
+        assert_eq!(builder.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(builder.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(builder.read_timeout, Some(Duration::from_secs(10)));
+        assert_eq!(builder.headers, vec![("X-Token".to_string(), "secret".to_string())]);
+        assert_eq!(builder.redirect, RedirectPolicy::None);
+        assert_eq!(builder.max_body_size, Some(1_024));
+        assert!(builder.http1_only);
+        assert!(!builder.gzip);
+    }
+
+    #[tokio::test]
+    async fn test_builder_invalid_header() {
+        match HttpDirectoryBuilder::new().header("Bad Header", "value").build("http://127.0.0.1/").await {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert_eq!(e.to_string(), "Invalid header 'Bad Header' for the request client"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_build_from_html() {
+        let body = "<ul>\n<li><a href=\"dir/\">dir/</a></li>\n<li><a href=\"file\">file</a></li>\n</ul>";
+        let fetcher =
+            InMemoryFetcher::new().with_body("http://example.org/pub/dir/", r#"<ul><li><a href="a">a</a></li></ul>"#);
+
+        let httpdir =
+            match HttpDirectoryBuilder::new().fetcher(fetcher).build_from_html("http://example.org/pub/", body) {
+                Ok(httpdir) => httpdir,
+                Err(e) => panic!("{e}"),
+            };
+        assert_eq!(httpdir.len(), 2);
+        assert_eq!(httpdir.site_type().map(SiteType::name), Some("ul"));
+
+        match httpdir.cd("dir/").await {
+            Ok(httpdir) => {
+                assert_eq!(httpdir.get_url().as_str(), "http://example.org/pub/dir/");
+                assert_eq!(httpdir.len(), 1);
+            }
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn test_builder_timezone() {
+        let body = "<pre><a href=\"local.iso\">local.iso</a>  2025-10-20 16:17  1.0M\n<a href=\"utc.iso\">utc.iso</a>  2025-10-20T14:17Z  1.0M\n</pre>";
+        let paris = FixedOffset::east_opt(2 * 3600).unreachable();
+        let httpdir =
+            HttpDirectoryBuilder::new().timezone(paris).build_from_html("http://example.org/", body).unreachable();
+
+        let dates: Vec<_> =
+            httpdir.entries().iter().filter_map(|entry| entry.date().map(|date| date.to_rfc3339())).collect();
+        assert_eq!(dates, vec!["2025-10-20T16:17:00+02:00", "2025-10-20T14:17:00+00:00"]);
+        assert_eq!(httpdir.entries()[0].date(), httpdir.entries()[1].date());
+    }
+
+    #[test]
+    fn test_builder_date_formats() {
+        let body = "<pre><a href=\"fr.iso\">fr.iso</a>  05-Avr-2024 11:59  1.0M\n<a href=\"dot.iso\">dot.iso</a>  05.04.2024 11h59  1.0M\n</pre>";
+
+        let httpdir = HttpDirectoryBuilder::new().build_from_html("http://example.org/", body).unreachable();
+        assert!(httpdir.entries().iter().all(|entry| entry.date().is_none()));
+
+        let httpdir = HttpDirectoryBuilder::new()
+            .month_names(MonthNames::french())
+            .date_format("%d.%m.%Y %Hh%M")
+            .build_from_html("http://example.org/", body)
+            .unreachable();
+        let dates: Vec<_> =
+            httpdir.entries().iter().filter_map(|entry| entry.date().map(|date| date.to_rfc3339())).collect();
+        assert_eq!(dates, vec!["2024-04-05T11:59:00+00:00", "2024-04-05T11:59:00+00:00"]);
+    }
+
+    #[test]
+    fn test_builder_build_from_html_bad_url() {
+        match HttpDirectoryBuilder::new().build_from_html("not an url", "") {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => {
+                assert_eq!(e.to_string(), "Error while parsing url 'not an url':\n -> relative URL without a base");
+            }
+        }
+    }
+}
@@ -1,14 +1,23 @@
-use crate::entry::Entry;
-use chrono::NaiveDateTime;
+use crate::{
+    checksum::Checksum,
+    date::DateParser,
+    entry::{Entry, UnitSystem},
+    error::{HttpDirError, Result},
+};
+use chrono::{DateTime, FixedOffset};
 use regex::Regex;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::Path;
 use tracing::trace;
+use url::Url;
 
 /// `HttpDirectoryEntry` represents either the `ParentDirectory`,
 /// a `Directory` or a `File` that have a name, link, date and size
 /// `Entry`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HttpDirectoryEntry {
     /// Parent directory with its link (as a `String`) to the effective parent directory
     ParentDirectory(String),
@@ -29,8 +38,9 @@ pub enum CompareField {
 
 impl HttpDirectoryEntry {
     /// Creates a new `HttpDirectoryEntry` entry with name, date, size and link
-    /// string slices
-    pub(crate) fn new(name: &str, date: &str, size: &str, link: &str) -> Self {
+    /// string slices. An entry whose size contains '-' is a directory,
+    /// a "parent directory" name or a ".." link is a parent directory.
+    pub fn new(name: &str, date: &str, size: &str, link: &str) -> Self {
         trace!("name: {name}, date: {date}, size: {size}, link: {link}");
         if name.to_lowercase() == "parent directory"
             || name.to_lowercase() == "parent directory/"
@@ -41,8 +51,11 @@ impl HttpDirectoryEntry {
             return HttpDirectoryEntry::ParentDirectory(link.to_string());
         }
 
-        let entry = Entry::new(name.trim(), link.trim(), date.trim(), size.trim());
+        HttpDirectoryEntry::from_entry(Entry::new(name.trim(), link.trim(), date.trim(), size.trim()))
+    }
 
+    // Returns a directory or a file from `entry`
+    fn from_entry(entry: Entry) -> Self {
         // `size` may be flipped with `date` so using the one returned
         // by `Entry::new()` in `entry` variable: that is likely to be
         // more accurate. `-` and `—` are different character. The latter
@@ -137,13 +150,135 @@ impl HttpDirectoryEntry {
     /// file.
     /// Returns None otherwise
     #[must_use]
-    pub fn date(&self) -> Option<NaiveDateTime> {
+    pub fn date(&self) -> Option<DateTime<FixedOffset>> {
         match self {
             HttpDirectoryEntry::ParentDirectory(_) => None,
             HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.date(),
         }
     }
 
+    /// Returns an `Option` with the description of the directory or the file
+    /// corresponding to the `HttpDirectoryEntry` if the listing has one.
+    /// Returns None otherwise (and always for a parent directory)
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) => None,
+            HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.description(),
+        }
+    }
+
+    /// Returns an `Option` with the value of the column named `header` (type,
+    /// owner, ...) printed by the listing for the directory or the file
+    /// corresponding to the `HttpDirectoryEntry`.
+    /// Returns None otherwise (and always for a parent directory)
+    #[must_use]
+    pub fn extra_column(&self, header: &str) -> Option<&str> {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) => None,
+            HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.extra_column(header),
+        }
+    }
+
+    // Sets the description and the other columns of a directory or a file
+    #[must_use]
+    pub(crate) fn with_columns(mut self, description: Option<String>, extra_columns: BTreeMap<String, String>) -> Self {
+        if let HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) = &mut self {
+            entry.set_columns(description, extra_columns);
+        }
+        self
+    }
+
+    /// Returns an `Option` with the absolute url of the directory or the file
+    /// corresponding to the `HttpDirectoryEntry` if it has been resolved when
+    /// scraping the listing.
+    /// Returns None otherwise (and always for a parent directory)
+    #[must_use]
+    pub fn url(&self) -> Option<&Url> {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) => None,
+            HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.url(),
+        }
+    }
+
+    // Resolves the url of a directory or a file against `base`
+    pub(crate) fn resolve_url(&mut self, base: &Url) {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) => (),
+            HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.resolve_url(base),
+        }
+    }
+
+    // Reads again the size of the directory or the file with `system`
+    pub(crate) fn set_unit_system(&mut self, system: UnitSystem) {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) => (),
+            HttpDirectoryEntry::File(entry) | HttpDirectoryEntry::Directory(entry) => entry.set_unit_system(system),
+        }
+    }
+
+    // Parses the date of the directory or the file with the user
+    // settings of `parser` (see `Entry::parse_date()`). The entry
+    // is classified again as the date may have been found in place
+    // of its size.
+    pub(crate) fn parse_date(&mut self, parser: &DateParser) {
+        *self = match std::mem::replace(self, HttpDirectoryEntry::ParentDirectory(String::new())) {
+            HttpDirectoryEntry::File(mut entry) | HttpDirectoryEntry::Directory(mut entry) => {
+                entry.parse_date(parser);
+                HttpDirectoryEntry::from_entry(entry)
+            }
+            parent @ HttpDirectoryEntry::ParentDirectory(_) => parent,
+        };
+    }
+
+    /// Returns an `Option` with the expected digest of the file corresponding
+    /// to the `HttpDirectoryEntry` if it has been found in a checksum manifest.
+    /// Returns None otherwise (and always for a directory)
+    #[must_use]
+    pub fn checksum(&self) -> Option<&Checksum> {
+        match self {
+            HttpDirectoryEntry::ParentDirectory(_) | HttpDirectoryEntry::Directory(_) => None,
+            HttpDirectoryEntry::File(entry) => entry.checksum(),
+        }
+    }
+
+    // Sets the expected digest of a file
+    pub(crate) fn set_checksum(&mut self, checksum: Checksum) {
+        if let HttpDirectoryEntry::File(entry) = self {
+            entry.set_checksum(checksum);
+        }
+    }
+
+    /// Verifies that the file at `path` (usually downloaded with
+    /// `HttpDirectory::download()`) matches the expected digest
+    /// of this file.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    /// - this entry has no expected digest
+    /// - the file at `path` can not be read
+    /// - the digest of the file at `path` is not the expected one
+    pub async fn verify(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let Some(checksum) = self.checksum() else {
+            return Err(HttpDirError::MissingChecksum {
+                name: self.name().unwrap_or("..").to_string(),
+            });
+        };
+
+        let actual = checksum.compute_file(path).await?;
+        if actual == checksum.digest() {
+            Ok(())
+        } else {
+            Err(HttpDirError::ChecksumMismatch {
+                path: path.display().to_string(),
+                expected: checksum.to_string(),
+                actual,
+            })
+        }
+    }
+
     /// Compares entries by the selected field from `CompareField` enum using
     /// ascending sorting order when `ascending` is `true` and descending order otherwise
     #[must_use]
@@ -196,7 +331,7 @@ pub enum EntryType {
 /// This function is used for testing the library and not intended
 /// for any other usage. Compares the size with the apparent size.
 #[cfg(any(test, feature = "test-helpers"))]
-pub fn assert_entry(dir_entry: &HttpDirectoryEntry, entry_type: &EntryType, name: &str, size: usize, date_str: &str) {
+pub fn assert_entry(dir_entry: &HttpDirectoryEntry, entry_type: &EntryType, name: &str, size: u64, date_str: &str) {
     // Use `cargo test --features test-output -- --nocapture` to show outputs while testing
     #[cfg(feature = "test-output")]
     println!("{dir_entry:?}, {entry_type:?}, {name}, {size}, {date_str}");
@@ -256,7 +391,14 @@ mod tests {
         assert_eq!(httpdirectoryentry.name(), Some("name"));
         assert_eq!(
             httpdirectoryentry.date(),
-            Some(NaiveDate::from_ymd_opt(2025, 05, 20).unreachable().and_hms_opt(20, 19, 00).unreachable())
+            Some(
+                NaiveDate::from_ymd_opt(2025, 05, 20)
+                    .unreachable()
+                    .and_hms_opt(20, 19, 00)
+                    .unreachable()
+                    .and_utc()
+                    .into()
+            )
         );
     }
 
@@ -332,4 +474,22 @@ mod tests {
         assert_eq!(file1.cmp_by_field(&file2, &CompareField::Size, false), Ordering::Greater);
         assert_eq!(file2.cmp_by_field(&file1, &CompareField::Size, false), Ordering::Less);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_httpdirectoryentry_serde_round_trip() {
+        let entries = vec![
+            HttpDirectoryEntry::new("parent directory", "", "-", "../"),
+            HttpDirectoryEntry::new("dir", "2025-05-20 20:19", "-", "dir/"),
+            HttpDirectoryEntry::new("name", "2025-05-20 20:19", "5.0K", "link"),
+        ];
+
+        let json = serde_json::to_string(&entries).unreachable();
+        let back: Vec<HttpDirectoryEntry> = serde_json::from_str(&json).unreachable();
+
+        assert_eq!(format!("{back:?}"), format!("{entries:?}"));
+        assert!(matches!(back[0], HttpDirectoryEntry::ParentDirectory(ref link) if link == "../"));
+        assert!(back[1].is_directory());
+        assert!(back[2].is_file());
+    }
 }
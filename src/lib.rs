@@ -1,10 +1,9 @@
 #![doc = include_str!("../README.md")]
 use const_format::formatcp;
 
-pub(crate) mod detect;
+pub(crate) mod conditional;
 pub(crate) mod requests;
 pub(crate) mod scrape;
-pub(crate) mod scrapers;
 
 /// All errors that you might get from httpdirectory library
 pub mod error;
@@ -13,6 +12,10 @@ pub mod error;
 /// in a structure with convenient methods
 pub mod httpdirectory;
 
+/// Module that provides a builder to configure the HTTP client
+/// used by an `HttpDirectory`
+pub mod httpdirectorybuilder;
+
 /// Module to deal with `HttpDirectoryEntry` enum that tells whether the
 /// `Entry` is a Parent directory, a directory or a file.
 pub mod httpdirectoryentry;
@@ -20,6 +23,54 @@ pub mod httpdirectoryentry;
 /// Module that helps storing all information about the entry (name, date, size and link)
 pub mod entry;
 
+/// Module that tells which listing format (`SiteType`) has been
+/// detected for an `HttpDirectory`
+pub mod detect;
+
+/// Module that provides the `Fetcher` trait used to retrieve
+/// listings and an in-memory implementation
+pub mod fetcher;
+
+/// Module that provides the `DiskCache` of listings that may be
+/// used by the HTTP client (see `HttpDirectoryBuilder::disk_cache()`)
+pub mod cache;
+
+/// Module that parses checksum manifests (`SHA256SUMS`, `SHA512SUMS`
+/// and `MD5SUMS`) and verifies files against their digests
+pub mod checksum;
+
+/// Module that provides the `MonthNames` used to read dates
+/// printed in other languages than English
+pub mod date;
+
+/// Module that computes the differences (`ListingDiff`) between
+/// two listings with `HttpDirectory::diff()`
+pub mod diff;
+
+/// Module that provides the `Progress` of files downloaded
+/// with `HttpDirectory::download_with_progress()`
+pub mod download;
+
+/// Module that provides the `ParseOptions` used to read the dates
+/// of listings and to bound the pages of paginated listings
+pub mod parseoptions;
+
+/// Module that provides the `RetryPolicy` used to retry requests
+/// that failed with a transient error
+pub mod retry;
+
+/// Module that provides the `Scraper` trait and the registry of
+/// scrapers used to understand directory listings
+pub mod scrapers;
+
+/// Module that provides the `WatchEvent` returned by
+/// `HttpDirectory::watch()` when a listing changes
+pub mod watch;
+
+/// Module that provides options to walk recursively an index
+/// tree with `HttpDirectory::walk()`
+pub mod walk;
+
 /// Module that will give access to a structure that will contain some statistics
 /// about an `HttpDirectory` by calling its `stats()` method
 pub mod stats;
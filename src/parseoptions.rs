@@ -0,0 +1,111 @@
+use crate::{
+    date::{DateParser, MonthNames},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrapers::Scraper,
+};
+use chrono::FixedOffset;
+
+/// Default maximum number of pages retrieved for a paginated
+/// listing (S3 buckets answer up to 1000 keys per page)
+pub const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Options used to read the listings understood by the scrapers:
+/// the settings used to parse their dates (user date formats, month
+/// names and default timezone) and the maximum number of pages of
+/// a paginated listing. An `HttpDirectory` carries the options set
+/// on its `HttpDirectoryBuilder`.
+///
+/// ```rust
+///   use httpdirectory::{date::MonthNames, parseoptions::ParseOptions, scrapers::ScraperRegistry};
+///   let options = ParseOptions::new().month_names(MonthNames::french()).date_format("%d.%m.%Y %Hh%M");
+///   let body = "<pre><a href=\"file\">file</a>  05-Avr-2024 11:59  12K\n</pre>";
+///   if let Ok(entries) = ScraperRegistry::new().scrape(body, &options) {
+///       println!("{entries:?}");
+///   }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    dates: DateParser,
+    max_pages: usize,
+}
+
+impl Default for ParseOptions {
+    /// Built-in date formats only, dates in UTC and at most
+    /// `DEFAULT_MAX_PAGES` pages per listing
+    fn default() -> Self {
+        ParseOptions {
+            dates: DateParser::default(),
+            max_pages: DEFAULT_MAX_PAGES,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns default `ParseOptions`
+    #[must_use]
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    /// Sets the timezone of dates printed without any offset by
+    /// listings (they are in UTC by default)
+    #[must_use]
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.dates.set_timezone(timezone);
+        self
+    }
+
+    /// Adds a `strftime` like date `format` (see [`chrono::format::strftime`])
+    /// tried after the built-in ones on dates that they do not understand
+    #[must_use]
+    pub fn date_format(mut self, format: &str) -> Self {
+        self.dates.add_format(format);
+        self
+    }
+
+    /// Adds the month names of a language: dates that contain one of
+    /// them are translated to English and tried again with the built-in
+    /// and user formats
+    #[must_use]
+    pub fn month_names(mut self, month_names: MonthNames) -> Self {
+        self.dates.add_month_names(month_names);
+        self
+    }
+
+    /// Sets the maximum number of pages (at least 1) retrieved for
+    /// a paginated listing: retrieving a listing with more pages fails
+    #[must_use]
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    // Maximum number of pages retrieved for a paginated listing
+    pub(crate) fn page_limit(&self) -> usize {
+        self.max_pages
+    }
+
+    // Completes `entries` scraped by `scraper`: sizes are read with
+    // the unit system of `scraper`, dates that the built-in formats
+    // did not understand are parsed with the user formats and month
+    // names and the timezone (if any) is applied to local dates
+    pub(crate) fn complete_entries(&self, scraper: &dyn Scraper, entries: &mut [HttpDirectoryEntry]) {
+        let unit_system = scraper.unit_system();
+        for entry in entries {
+            entry.set_unit_system(unit_system);
+            entry.parse_date(&self.dates);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_MAX_PAGES, ParseOptions};
+
+    #[test]
+    fn test_parse_options_max_pages() {
+        assert_eq!(ParseOptions::new().page_limit(), DEFAULT_MAX_PAGES);
+        assert_eq!(ParseOptions::new().max_pages(5).page_limit(), 5);
+        assert_eq!(ParseOptions::new().max_pages(0).page_limit(), 1);
+    }
+}
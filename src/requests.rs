@@ -1,81 +1,320 @@
 use crate::{
-    HTTPDIR_USER_AGENT,
+    cache::DiskCache,
     error::{HttpDirError, ParseResultExt, ReqwestResultExt, Result},
+    fetcher::{FetchResponse, FetchStream, Fetcher},
+    httpdirectorybuilder::{HttpDirectoryBuilder, RedirectPolicy},
+    retry::{RetryPolicy, RetryableError, parse_retry_after},
 };
-use reqwest::{Client, Response, StatusCode};
-use std::time::Duration;
-use tracing::{error, trace};
+use chrono::Utc;
+use futures::{StreamExt, future::BoxFuture, stream};
+use reqwest::{
+    Client, Response, StatusCode,
+    header::{ACCEPT_ENCODING, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, RANGE, RETRY_AFTER},
+    redirect,
+};
+use tracing::{trace, warn};
+use unwrap_unreachable::UnwrapUnreachable;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(crate) struct Request {
     client: Client,
+    max_body_size: Option<u64>,
+    retry: RetryPolicy,
+    disk_cache: Option<DiskCache>,
+}
+
+// Builds the default headers from the (name, value) pairs
+// given to the builder
+fn build_headers(headers: &[(String, String)]) -> Result<HeaderMap> {
+    let mut header_map = HeaderMap::new();
+
+    for (name, value) in headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(header_name), Ok(header_value)) => {
+                header_map.append(header_name, header_value);
+            }
+            _ => {
+                return Err(HttpDirError::InvalidHeader {
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(header_map)
+}
+
+// Tells whether `error` is one of the errors that a
+// `RetryPolicy` may retry
+fn retryable_error(error: &reqwest::Error) -> Option<RetryableError> {
+    if error.is_timeout() {
+        Some(RetryableError::Timeout)
+    } else if error.is_connect() {
+        Some(RetryableError::Connect)
+    } else {
+        None
+    }
+}
+
+// Decodes `bytes` with the charset found in the `Content-Type`
+// header if any. Defaults to UTF-8 as reqwest does.
+fn decode_body(content_type: Option<&HeaderValue>, bytes: &[u8]) -> String {
+    let encoding = content_type
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').find_map(|param| param.trim().strip_prefix("charset=")))
+        .and_then(|charset| encoding_rs::Encoding::for_label(charset.trim_matches('"').as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
 }
 
 impl Request {
-    /// Returns a new reqwest client that will be used to make
-    /// HTTP requests. `timeout_s` optionally defines a global
-    /// request timeout in seconds
+    /// Returns a new reqwest client configured with the
+    /// settings of `builder` that will be used to make
+    /// HTTP requests.
     ///
     /// # Errors
     ///
     /// Returns an error if the request client can not be built
-    pub(crate) fn new(timeout_s: Option<u64>) -> Result<Self> {
-        let client_builder;
-        if let Some(timeout_s) = timeout_s {
-            client_builder = Client::builder().user_agent(HTTPDIR_USER_AGENT).timeout(Duration::from_secs(timeout_s));
-        } else {
-            client_builder = Client::builder().user_agent(HTTPDIR_USER_AGENT);
+    /// or if one of the default headers is invalid
+    pub(crate) fn new(builder: &HttpDirectoryBuilder) -> Result<Self> {
+        let redirect = match builder.redirect {
+            RedirectPolicy::None => redirect::Policy::none(),
+            RedirectPolicy::Limited(max) => redirect::Policy::limited(max),
+        };
+
+        let mut client_builder = Client::builder()
+            .user_agent(&builder.user_agent)
+            .default_headers(build_headers(&builder.headers)?)
+            .redirect(redirect)
+            .gzip(builder.gzip);
+
+        if let Some(timeout) = builder.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+        if let Some(timeout) = builder.connect_timeout {
+            client_builder = client_builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = builder.read_timeout {
+            client_builder = client_builder.read_timeout(timeout);
+        }
+        if builder.http1_only {
+            client_builder = client_builder.http1_only();
         }
 
         let client = client_builder.build().with()?;
         trace!("New reqwest client: {client:?}");
         Ok(Request {
             client,
+            max_body_size: builder.max_body_size,
+            retry: builder.retry.clone(),
+            disk_cache: builder.disk_cache.clone(),
         })
     }
 
-    /// Returns the content of an url if any
-    ///
-    /// # Errors
-    ///
-    /// Returns an error when the reqwest could not be made or that the server
-    /// did not respond with a 200 HTTP status code.
-    pub(crate) async fn get(&self, url: &str) -> Result<Response> {
+    // Sends a GET request to `url` with the additional `headers`
+    // and retries transient failures according to the retry policy.
+    // Returns the last response whatever its HTTP status code is.
+    async fn send(&self, url: &str, headers: HeaderMap) -> Result<Response> {
         url::Url::parse(url).with_url(url)?;
 
-        trace!("Requesting '{url}'");
+        let mut attempt = 1;
+        loop {
+            trace!("Requesting '{url}' with {headers:?} (attempt {attempt})");
+
+            let (delay, result) = match self.client.get(url).headers(headers.clone()).send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| parse_retry_after(value, Utc::now()));
+                    let delay = self.retry.delay_for_status(attempt, response.status().as_u16(), retry_after);
+                    (delay, Ok(response))
+                }
+                Err(e) => (self.retry.delay_for_error(attempt, retryable_error(&e)), Err(e)),
+            };
 
-        match self.client.get(url).send().await.with_url(url) {
-            Ok(response) if response.status() == StatusCode::OK => Ok(response),
-            Ok(response) => {
-                error!("Error while retrieving url {url} content: {}", response.status());
-                Err(HttpDirError::HttpResponse {
-                    url: url.to_string(),
-                    status_code: response.status(),
-                })
+            match delay {
+                Some(delay) => {
+                    warn!("Retrying url {url} in {delay:?} after attempt {attempt} failed");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return result.with_url(url),
             }
-            Err(e) => Err(e),
+        }
+    }
+
+    // Returns the decoded body of `response` and fails
+    // when it is bigger than the maximum body size
+    async fn read_body(&self, url: &str, response: Response) -> Result<String> {
+        match self.max_body_size {
+            Some(max_size) => read_limited_body(url, response, max_size).await,
+            None => response.text().await.with_url(url),
         }
     }
 }
 
+impl Fetcher for Request {
+    /// Returns the status, headers and decoded body of `url`.
+    /// Transient failures are retried according to the retry
+    /// policy. The body of a response whose HTTP status code is
+    /// not 200 is read on a best effort basis.
+    fn fetch<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<FetchResponse>> {
+        self.fetch_with_headers(url, &[])
+    }
+
+    /// Same as `fetch()` with the additional request `headers`.
+    /// With a disk cache, fresh cached listings are returned
+    /// without any request and 200 responses are stored. A 304
+    /// response makes the cached listing fresh again.
+    fn fetch_with_headers<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<FetchResponse>> {
+        Box::pin(async move {
+            if let Some(cache) = &self.disk_cache {
+                if let Some(response) = cache.get(url).await {
+                    return Ok(response);
+                } else if cache.is_offline() {
+                    return Err(HttpDirError::OfflineCacheMiss {
+                        url: url.to_string(),
+                    });
+                }
+            }
+
+            let response = self.send(url, build_headers(headers)?).await?;
+            trace!("Response to get '{url}': {response:?}");
+
+            let status = response.status();
+            let headers = collect_headers(&response);
+            let body = if status == StatusCode::OK {
+                self.read_body(url, response).await?
+            } else {
+                self.read_body(url, response).await.unwrap_or_default()
+            };
+
+            let response = FetchResponse {
+                status: status.as_u16(),
+                headers,
+                body,
+            };
+            if let Some(cache) = &self.disk_cache {
+                let stored = match response.status {
+                    200 => cache.store(url, &response).await,
+                    304 => cache.refresh(url).await,
+                    _ => Ok(()),
+                };
+                if let Err(e) = stored {
+                    warn!("Unable to cache {url}: {e}");
+                }
+            }
+
+            Ok(response)
+        })
+    }
+
+    /// Returns the status, headers and body of `url` as a stream
+    /// of chunks of bytes. A `Range` header is sent when `offset`
+    /// is not 0. The maximum body size does not apply here.
+    fn fetch_stream<'a>(&'a self, url: &'a str, offset: u64) -> BoxFuture<'a, Result<FetchStream>> {
+        Box::pin(async move {
+            let mut headers = HeaderMap::new();
+            if offset > 0 {
+                headers.insert(RANGE, HeaderValue::from_str(&format!("bytes={offset}-")).unreachable());
+                // Offsets are counted in bytes of the file, not of a compressed body
+                headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+            }
+            let response = self.send(url, headers).await?;
+            trace!("Response to get '{url}' from byte {offset}: {response:?}");
+
+            let status = response.status().as_u16();
+            let headers = collect_headers(&response);
+            let url = url.to_string();
+            let body = stream::unfold(Some(response), move |response| {
+                let url = url.clone();
+                async move {
+                    let mut response = response?;
+                    match response.chunk().await.with_url(&url) {
+                        Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), Some(response))),
+                        Ok(None) => None,
+                        Err(e) => Some((Err(e), None)),
+                    }
+                }
+            })
+            .boxed();
+
+            Ok(FetchStream {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+// Collects the headers of `response` as (name, value) pairs
+// leaving out values that are not valid strings
+fn collect_headers(response: &Response) -> Vec<(String, String)> {
+    response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+// Reads the body of `response` chunk by chunk and stops
+// as soon as it is bigger than `max_size` bytes
+async fn read_limited_body(url: &str, mut response: Response, max_size: u64) -> Result<String> {
+    let too_large = || HttpDirError::BodyTooLarge {
+        url: url.to_string(),
+        max_size,
+    };
+
+    if response.content_length().is_some_and(|length| length > max_size) {
+        return Err(too_large());
+    }
+
+    let content_type = response.headers().get(CONTENT_TYPE).cloned();
+    let mut bytes = vec![];
+    while let Some(chunk) = response.chunk().await.with_url(url)? {
+        if (bytes.len() + chunk.len()) as u64 > max_size {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(decode_body(content_type.as_ref(), &bytes))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_bad_url() {
-        match Request::new(None) {
-            Ok(client) => assert!(client.get("this_is_not_a valid_url").await.is_err()),
+        match Request::new(&HttpDirectoryBuilder::default()) {
+            Ok(client) => assert!(client.fetch("this_is_not_a valid_url").await.is_err()),
             Err(e) => panic!("This test failed: {e}"),
         }
     }
 
     #[tokio::test]
     async fn test_url_does_not_exists() {
-        match Request::new(None) {
-            Ok(client) => assert!(client.get("https://this-does-not-exists.org/").await.is_err()),
+        match Request::new(&HttpDirectoryBuilder::default()) {
+            Ok(client) => assert!(client.fetch("https://this-does-not-exists.org/").await.is_err()),
             Err(e) => panic!("This test failed: {e}"),
         }
     }
+
+    #[test]
+    fn test_decode_body_with_charset() {
+        let content_type = HeaderValue::from_static("text/html; charset=ISO-8859-1");
+        assert_eq!(decode_body(Some(&content_type), b"caf\xe9"), "café");
+        assert_eq!(decode_body(None, "café".as_bytes()), "café");
+    }
 }
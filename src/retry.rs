@@ -0,0 +1,286 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Errors (other than HTTP status codes) that may be retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableError {
+    /// The request timed out
+    Timeout,
+
+    /// The request could not connect to the server
+    Connect,
+}
+
+/// Retry policy applied to each request made by an `HttpDirectory`
+/// (`cd()` and `walk()` included) when the server answers with a
+/// retryable HTTP status code or when a retryable error occurs.
+/// Delays between attempts grow exponentially from `initial_backoff`
+/// up to `max_backoff` and a random jitter may be applied. A
+/// `Retry-After` header sent by the server replaces the computed
+/// delay (it is capped to `max_backoff`).
+///
+/// ```rust
+///   use httpdirectory::{httpdirectorybuilder::HttpDirectoryBuilder, retry::RetryPolicy};
+///   use std::time::Duration;
+///   async fn retry_example() {
+///     let policy = RetryPolicy::new().max_attempts(5).initial_backoff(Duration::from_secs(1));
+///     if let Ok(httpdir) = HttpDirectoryBuilder::new().retry_policy(policy).build("https://cloud.debian.org/images/cloud/").await {
+///         println!("{:?}", httpdir.dirs());
+///     }
+///   }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts for one request (1 means
+    /// that the request is never retried)
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Maximum delay between two attempts
+    pub max_backoff: Duration,
+
+    /// Applies a random jitter to the computed delays (each delay
+    /// is then randomly chosen between half and all of its value)
+    pub jitter: bool,
+
+    /// HTTP status codes that are considered as transient failures
+    pub retryable_statuses: Vec<u16>,
+
+    /// Errors that are considered as transient failures
+    pub retryable_errors: Vec<RetryableError>,
+
+    /// Uses the delay given by a `Retry-After` header if any
+    pub honor_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, delays from 500 ms up to 30 s with jitter,
+    /// retries 502, 503 and 504 status codes and timeouts and
+    /// honors `Retry-After`. This is the policy used by a default
+    /// `HttpDirectoryBuilder`: use `retryable_statuses()` and
+    /// `retryable_errors()` to retry more (429, connection
+    /// errors, ...)
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![502, 503, 504],
+            retryable_errors: vec![RetryableError::Timeout],
+            honor_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the default `RetryPolicy`
+    #[must_use]
+    pub fn new() -> Self {
+        RetryPolicy::default()
+    }
+
+    /// Returns a policy that never retries any request
+    #[must_use]
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Sets the maximum number of attempts (at least 1)
+    #[must_use]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry
+    #[must_use]
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts
+    #[must_use]
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Enables or disables the random jitter
+    #[must_use]
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the HTTP status codes that are retried
+    #[must_use]
+    pub fn retryable_statuses(mut self, statuses: &[u16]) -> Self {
+        self.retryable_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Sets the errors that are retried
+    #[must_use]
+    pub fn retryable_errors(mut self, errors: &[RetryableError]) -> Self {
+        self.retryable_errors = errors.to_vec();
+        self
+    }
+
+    /// Honors (or not) the `Retry-After` header
+    #[must_use]
+    pub fn honor_retry_after(mut self, honor: bool) -> Self {
+        self.honor_retry_after = honor;
+        self
+    }
+
+    // Exponential backoff before the retry that follows
+    // `attempt` (starting at 1) with jitter if enabled
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.initial_backoff.saturating_mul(factor).min(self.max_backoff);
+
+        if self.jitter {
+            delay.mul_f64(0.5 + fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+
+    // Returns the delay to wait before retrying a request whose
+    // `attempt` (starting at 1) got `status` or None if it must
+    // not be retried
+    pub(crate) fn delay_for_status(
+        &self,
+        attempt: u32,
+        status: u16,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retryable_statuses.contains(&status) {
+            return None;
+        }
+
+        match retry_after {
+            Some(retry_after) if self.honor_retry_after => Some(retry_after.min(self.max_backoff)),
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    // Returns the delay to wait before retrying a request whose
+    // `attempt` (starting at 1) failed with `error` or None if it
+    // must not be retried
+    pub(crate) fn delay_for_error(&self, attempt: u32, error: Option<RetryableError>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_some_and(|error| self.retryable_errors.contains(&error)) {
+            None
+        } else {
+            Some(self.backoff(attempt))
+        }
+    }
+}
+
+// Parses a `Retry-After` header value that is either a number
+// of seconds or an HTTP date (that may already be passed)
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        Some(Duration::from_secs(seconds))
+    } else {
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+        Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetryPolicy, RetryableError, parse_retry_after};
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    #[test]
+    fn test_retry_policy_none() {
+        let policy = RetryPolicy::none();
+
+        assert_eq!(policy.delay_for_status(1, 503, None), None);
+        assert_eq!(policy.delay_for_error(1, Some(RetryableError::Timeout)), None);
+    }
+
+    #[test]
+    fn test_retry_policy_default() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.max_attempts, 3);
+        assert!(policy.delay_for_status(1, 503, None).is_some());
+        assert!(policy.delay_for_status(2, 504, None).is_some());
+        assert_eq!(policy.delay_for_status(3, 502, None), None);
+        assert_eq!(policy.delay_for_status(1, 500, None), None);
+        assert_eq!(policy.delay_for_status(1, 429, None), None);
+        assert!(policy.delay_for_error(1, Some(RetryableError::Timeout)).is_some());
+        assert_eq!(policy.delay_for_error(1, Some(RetryableError::Connect)), None);
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(300))
+            .jitter(false)
+            .max_attempts(10);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff(64), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_retry_policy_jitter() {
+        let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(100));
+
+        for _ in 0..100 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_statuses_and_errors() {
+        let policy = RetryPolicy::new().jitter(false).retryable_errors(&[RetryableError::Timeout]);
+
+        assert_eq!(policy.delay_for_status(1, 503, None), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay_for_status(2, 502, None), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay_for_status(3, 503, None), None);
+        assert_eq!(policy.delay_for_status(1, 404, None), None);
+        assert_eq!(policy.delay_for_error(1, Some(RetryableError::Timeout)), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay_for_error(1, Some(RetryableError::Connect)), None);
+        assert_eq!(policy.delay_for_error(1, None), None);
+    }
+
+    #[test]
+    fn test_retry_policy_retry_after() {
+        let policy = RetryPolicy::new().max_backoff(Duration::from_secs(10)).retryable_statuses(&[429, 503]);
+
+        assert_eq!(policy.delay_for_status(1, 429, Some(Duration::from_secs(2))), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay_for_status(1, 429, Some(Duration::from_secs(120))), Some(Duration::from_secs(10)));
+
+        let policy = policy.honor_retry_after(false).jitter(false);
+        assert_eq!(policy.delay_for_status(1, 429, Some(Duration::from_secs(2))), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).single().unreachable();
+
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
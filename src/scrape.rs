@@ -1,10 +1,11 @@
 use crate::{
-    detect::{PureHtml, SiteType},
+    detect::detect_table,
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
-    scrapers::{h5ai::scrape_h5ai, miniserve::scrape_miniserve, snt::scrape_snt, stil::scrape_stil, ul::scrape_ul},
+    scrapers::{Confidence, Scraper},
 };
 use scraper::{ElementRef, Html, Selector};
+use std::collections::BTreeMap;
 use tracing::{debug, info, trace, warn};
 use unwrap_unreachable::UnwrapUnreachable;
 
@@ -85,12 +86,56 @@ pub(crate) fn build_entry<'a>(
     Some(HttpDirectoryEntry::new(name, date, size, link))
 }
 
+// Returns the headers of the columns of `table` as read
+// in the first row that has <th> cells
+fn table_headers(table: ElementRef, row_selector: &Selector) -> Vec<String> {
+    let th_selector = Selector::parse("th").unreachable();
+
+    table
+        .select(row_selector)
+        .map(|row| {
+            row.select(&th_selector)
+                .map(|th| remove_empty_cell(th.text().collect()).concat().trim().to_string())
+                .collect()
+        })
+        .find(|headers: &Vec<String>| !headers.is_empty())
+        .unwrap_or_default()
+}
+
+// Reads the columns left in a row once name, date and size have
+// been read: `first` is the index of the first of them. The one
+// with a "Description" header is the description and the others
+// are kept with their header. Columns without any header or any
+// text are ignored.
+pub(crate) fn extract_extra_columns(
+    columns: &[ElementRef],
+    first: usize,
+    headers: &[String],
+) -> (Option<String>, BTreeMap<String, String>) {
+    let mut description = None;
+    let mut extra_columns = BTreeMap::new();
+
+    for (col, header) in columns.iter().zip(headers.iter().skip(first)) {
+        let text = remove_empty_cell(col.text().collect()).iter().map(|t| t.trim()).collect::<Vec<_>>().join(" ");
+        if header.is_empty() || text.is_empty() {
+            continue;
+        }
+        if header.eq_ignore_ascii_case("description") {
+            description = Some(text);
+        } else {
+            extra_columns.insert(header.clone(), text);
+        }
+    }
+
+    (description, extra_columns)
+}
+
 // Parses `body` variable to find a table that may
-// have icon, name & link, date, size and description.
-// We do not mind description field. Sometimes icon
-// column (first one) is not empty (it has text) so
-// it may be that this is in fact the name & link
-// column,
+// have icon, name & link, date, size, description and
+// other columns named by the headers of the table.
+// Sometimes icon column (first one) is not empty (it
+// has text) so it may be that this is in fact the
+// name & link column,
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
 pub(crate) fn scrape_table(body: &str) -> Vec<HttpDirectoryEntry> {
     let html = Html::parse_document(body);
@@ -101,19 +146,30 @@ pub(crate) fn scrape_table(body: &str) -> Vec<HttpDirectoryEntry> {
 
     html.select(&table_selector)
         .filter(|&table| are_table_headers_present(table))
-        .flat_map(|table| table.select(&row_selector).collect::<Vec<_>>())
-        .filter_map(|row| {
-            let one_line: Vec<_> = row.select(&col_selector).collect();
-            let mut iter = one_line.iter();
-
-            let (name, link) = extract_name_and_link(&mut iter, &link_selector);
-            trace!("name: {name:?}, link: {link}");
-
-            let date = extract_col_text(&mut iter);
-            let size = extract_col_text(&mut iter);
-            trace!("date: {date:?}, size: {size:?}");
-
-            build_entry(&name, &date, &size, link)
+        .flat_map(|table| {
+            let headers = table_headers(table, &row_selector);
+            trace!("headers: {headers:?}");
+
+            table
+                .select(&row_selector)
+                .filter_map(|row| {
+                    let one_line: Vec<_> = row.select(&col_selector).collect();
+                    let mut iter = one_line.iter();
+
+                    let (name, link) = extract_name_and_link(&mut iter, &link_selector);
+                    trace!("name: {name:?}, link: {link}");
+
+                    let date = extract_col_text(&mut iter);
+                    let size = extract_col_text(&mut iter);
+                    trace!("date: {date:?}, size: {size:?}");
+
+                    let first = one_line.len() - iter.len();
+                    let (description, extra_columns) = extract_extra_columns(iter.as_slice(), first, &headers);
+                    trace!("description: {description:?}, extra columns: {extra_columns:?}");
+
+                    build_entry(&name, &date, &size, link).map(|entry| entry.with_columns(description, extra_columns))
+                })
+                .collect::<Vec<_>>()
         })
         .collect()
 }
@@ -151,8 +207,11 @@ fn scrape_pre_with_img(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
                         if name.to_lowercase() == "parent directory" {
                             http_dir_entry.push(HttpDirectoryEntry::ParentDirectory(link.to_string()));
                         } else {
-                            let (date, size) = get_date_and_size(href[1]);
-                            http_dir_entry.push(HttpDirectoryEntry::new(name, date, size, link));
+                            let (date, size, description) = get_date_size_and_description(href[1]);
+                            http_dir_entry.push(
+                                HttpDirectoryEntry::new(name, date, size, link)
+                                    .with_columns(description, BTreeMap::new()),
+                            );
                         }
                     }
                 }
@@ -178,7 +237,8 @@ pub(crate) fn remove_empty_cell(mut vector: Vec<&str>) -> Vec<&str> {
 // Forms of the line:
 // '2025-04-30 16:31  256M  Ubuntu Server 24.04 LTS (Noble Numbat) daily builds'
 // '13-May-2025 03:57                4836'  (no description here and date format is bigger)
-fn get_date_and_size(line: &str) -> (&str, &str) {
+// Returns the date, the size and the description if any
+fn get_date_size_and_description(line: &str) -> (&str, &str, Option<String>) {
     let index = match line.find(' ') {
         Some(index) => match line[index + 1..].find(' ') {
             Some(index2) => index + index2 + 1,
@@ -191,13 +251,14 @@ fn get_date_and_size(line: &str) -> (&str, &str) {
     let size_and_description = &line[index..];
 
     let line_split: Vec<&str> = size_and_description.trim().split(' ').collect();
-    let size = if line_split.len() >= 2 {
-        line_split[0]
+    let (size, description) = if line_split.len() >= 2 {
+        let description = size_and_description.trim()[line_split[0].len()..].trim();
+        (line_split[0], Some(description.to_string()))
     } else {
-        size_and_description
+        (size_and_description, None)
     };
-    trace!(" -> date: {date}, size: {size}");
-    (date, size)
+    trace!(" -> date: {date}, size: {size}, description: {description:?}");
+    (date, size, description)
 }
 
 // Form of the column:  '<a href="bionic/">bionic/'
@@ -230,6 +291,13 @@ pub fn get_link_and_name(column: &str) -> (&str, &str) {
     }
 }
 
+// Returns the link of an entry named `name` in listings that only
+// give names (JSON or XML): characters that have a meaning in urls
+// (`%`, `?` and `#`) are percent encoded
+pub(crate) fn link_from_name(name: &str) -> String {
+    name.replace('%', "%25").replace('?', "%3F").replace('#', "%23")
+}
+
 // Returns true if href vector contains
 // Name, Last modified, Size in this exact order
 // Some websites does not provides a description
@@ -285,8 +353,10 @@ fn scrape_pre_simple(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
                     if name.to_lowercase() == "../" {
                         http_dir_entry.push(HttpDirectoryEntry::ParentDirectory(link.to_string()));
                     } else {
-                        let (date, size) = get_date_and_size(href[1]);
-                        http_dir_entry.push(HttpDirectoryEntry::new(name, date, size, link));
+                        let (date, size, description) = get_date_size_and_description(href[1]);
+                        http_dir_entry.push(
+                            HttpDirectoryEntry::new(name, date, size, link).with_columns(description, BTreeMap::new()),
+                        );
                     }
                 }
             }
@@ -296,58 +366,62 @@ fn scrape_pre_simple(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
     Ok(http_dir_entry)
 }
 
-// Parses `body` that should contain an HTML page / body
-// to recognize (if possible) entries of files, directories or
-// a parent directory and fill a vector of `HttpDirectoryEntry`
-// accordingly
-pub fn scrape_body(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
-    match SiteType::detect(body) {
-        SiteType::H5ai(version) => {
-            info!("H5ai powered version {version} website detected");
-            scrape_h5ai(body, &version)
-        }
-        SiteType::Snt => {
-            info!("SNT index generator website detected");
-            scrape_snt(body)
-        }
-        SiteType::MiniServe(version) => {
-            info!("Miniserve version {version} website detected");
-            scrape_miniserve(body, &version)
+/// Scraper for pages that list entries in a `<table>` with
+/// a date column (Apache, nginx fancyindex, lighttpd, ...)
+#[derive(Debug)]
+pub(crate) struct TableScraper;
+
+impl Scraper for TableScraper {
+    fn name(&self) -> &'static str {
+        "table"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_table(body) {
+            60
+        } else {
+            0
         }
-        SiteType::Stil => {
-            info!("Stil STatic Index List website detected");
-            scrape_stil(body)
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Body has <table> tag");
+        Ok(scrape_table(body))
+    }
+}
+
+/// Scraper for pages that list entries in a `<pre>` formatted
+/// text with or without icons (Apache, nginx autoindex, ...)
+#[derive(Debug)]
+pub(crate) struct PreScraper;
+
+impl Scraper for PreScraper {
+    fn name(&self) -> &'static str {
+        "pre"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if body.contains("<pre>") {
+            40
+        } else {
+            0
         }
-        SiteType::NotNamed(html) => match html {
-            PureHtml::Table => {
-                info!("Body has <table> tag");
-                Ok(scrape_table(body))
-            }
-            PureHtml::Pre => {
-                info!("Body has <pre> tag");
-                let http_dir_entry = scrape_pre_with_img(body)?;
-                if http_dir_entry.is_empty() {
-                    let http_dir_entry = scrape_pre_simple(body)?;
-                    Ok(http_dir_entry)
-                } else {
-                    Ok(http_dir_entry)
-                }
-            }
-            PureHtml::Ul => {
-                info!("Body has no <table>, nor <pre> but <ul> tag");
-                scrape_ul(body)
-            }
-        },
-        SiteType::None => {
-            warn!("Site type has not been detected: doing nothing");
-            Ok(vec![])
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Body has <pre> tag");
+        let http_dir_entry = scrape_pre_with_img(body)?;
+        if http_dir_entry.is_empty() {
+            scrape_pre_simple(body)
+        } else {
+            Ok(http_dir_entry)
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::is_this_a_real_header;
+    use super::{get_date_size_and_description, is_this_a_real_header, scrape_table};
 
     #[test]
     fn test_is_this_a_real_header() {
@@ -362,4 +436,39 @@ mod test {
         let header = is_this_a_real_header(&href);
         assert!(!header);
     }
+
+    #[test]
+    fn test_get_date_size_and_description() {
+        assert_eq!(
+            get_date_size_and_description(
+                "2025-04-30 16:31  256M  Ubuntu Server 24.04 LTS (Noble Numbat) daily builds"
+            ),
+            ("2025-04-30 16:31", "256M", Some("Ubuntu Server 24.04 LTS (Noble Numbat) daily builds".to_string()))
+        );
+        assert_eq!(
+            get_date_size_and_description("13-May-2025 03:57                4836"),
+            ("13-May-2025 03:57", "                4836", None)
+        );
+    }
+
+    #[test]
+    fn test_scrape_table_extra_columns() {
+        let body = r#"<table>
+<tr><th></th><th>Name</th><th>Last modified</th><th>Size</th><th>Description</th><th>Type</th><th>Owner</th></tr>
+<tr><th colspan="7"><hr></th></tr>
+<tr><td><img src="/icons/text.gif"></td><td><a href="README">README</a></td><td>2025-04-30 04:21</td><td>20K</td><td>Read me first</td><td>text/plain</td><td>&nbsp;</td></tr>
+<tr><td><img src="/icons/folder.gif"></td><td><a href="iso/">iso/</a></td><td>2025-04-30 04:21</td><td>-</td><td>&nbsp;</td><td>Directory</td><td>root</td></tr>
+</table>"#;
+        let entries = scrape_table(body);
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].description(), Some("Read me first"));
+        assert_eq!(entries[0].extra_column("type"), Some("text/plain"));
+        assert_eq!(entries[0].extra_column("Owner"), None);
+
+        assert!(entries[1].is_directory());
+        assert_eq!(entries[1].description(), None);
+        assert_eq!(entries[1].extra_column("Type"), Some("Directory"));
+        assert_eq!(entries[1].extra_column("Owner"), Some("root"));
+    }
 }
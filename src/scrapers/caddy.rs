@@ -0,0 +1,152 @@
+use crate::{
+    detect::detect_caddy,
+    error::{JsonResultExt, Result, SelectorResultExt},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrape::link_from_name,
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper, is_content_type, shape_confidence},
+};
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use tracing::{info, trace};
+
+/// Scraper for the HTML template of Caddy's `file_server browse`
+/// (<https://caddyserver.com/docs/caddyfile/directives/file_server>)
+#[derive(Debug)]
+pub(crate) struct CaddyScraper;
+
+impl Scraper for CaddyScraper {
+    fn name(&self) -> &'static str {
+        "caddy"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_caddy(body) {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Caddy file server website detected");
+        scrape_caddy(body)
+    }
+}
+
+// Returns the trimmed text of the first element of `row`
+// selected by `selector` if any
+fn select_text(row: ElementRef, selector: &Selector) -> Option<String> {
+    row.select(selector).next().map(|element| element.text().collect::<String>().trim().to_string())
+}
+
+// Rows of the listing look like:
+// <tr class="file">
+//   <td></td>
+//   <td><a href="./debian/"><svg>...</svg><span class="name">debian/</span></a></td>
+//   <td data-order="-1">&mdash;</td>
+//   <td class="timestamp hideable"><time datetime="2025-05-31T13:54:45Z">05/31/2025 01:54:45 PM +00:00</time></td>
+//   <td class="hideable"></td>
+// </tr>
+// `data-order` is the exact size in bytes of a file (-1 for directories)
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_caddy(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let html = Html::parse_document(body);
+    let row_selector = Selector::parse("table tbody tr").with_selector("table tbody tr")?;
+    let link_selector = Selector::parse("td a").with_selector("td a")?;
+    let name_selector = Selector::parse("span.name").with_selector("span.name")?;
+    let size_selector = Selector::parse("td[data-order]").with_selector("td[data-order]")?;
+    let time_selector = Selector::parse("time").with_selector("time")?;
+
+    let entries = html
+        .select(&row_selector)
+        .filter_map(|row| {
+            let link = row.select(&link_selector).next()?;
+            let href = link.value().attr("href").unwrap_or_default();
+            let name =
+                select_text(row, &name_selector).unwrap_or_else(|| link.text().collect::<String>().trim().to_string());
+
+            let size = match row.select(&size_selector).next().and_then(|td| td.value().attr("data-order")) {
+                Some("-1") | None => "-",
+                Some(size) => size,
+            };
+            let date = select_text(row, &time_selector).unwrap_or_default();
+            trace!("name: {name}, link: {href}, date: {date}, size: {size}");
+
+            if href == ".." || href == "../" {
+                Some(HttpDirectoryEntry::ParentDirectory(href.to_string()))
+            } else {
+                Some(HttpDirectoryEntry::new(&name, &date, size, href))
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// Tells whether `body` is a JSON array of objects that all have
+// a `name`, an `is_dir` flag and a `mod_time`
+fn is_caddy_json(body: &str) -> bool {
+    if !body.trim_start().starts_with('[') {
+        return false;
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(items)) => items.iter().all(|item| {
+            item.get("name").is_some_and(Value::is_string)
+                && item.get("is_dir").is_some_and(Value::is_boolean)
+                && item.get("mod_time").is_some_and(Value::is_string)
+        }),
+        _ => false,
+    }
+}
+
+// Parses the JSON listing answered by Caddy to requests
+// with an `Accept: application/json` header:
+// [ {"name":"debian/","size":4096,"url":"./debian/","mod_time":"2025-05-31T13:54:45.123+02:00",
+//    "mode":2147484141,"is_dir":true,"is_symlink":false}, ... ]
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_caddy_json(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let items: Vec<Value> = serde_json::from_str(body).with_json()?;
+
+    let entries = items
+        .iter()
+        .filter_map(|item| {
+            let name = item.get("name")?.as_str()?;
+            let link = item.get("url").and_then(Value::as_str).map_or_else(|| link_from_name(name), str::to_string);
+            let mod_time = item.get("mod_time").and_then(Value::as_str).unwrap_or_default();
+            let size = if item.get("is_dir").and_then(Value::as_bool).unwrap_or_default() {
+                "-".to_string()
+            } else {
+                item.get("size").and_then(Value::as_u64).map(|size| size.to_string()).unwrap_or_default()
+            };
+            trace!("name: {name}, link: {link}, date: {mod_time}, size: {size}");
+            Some(HttpDirectoryEntry::new(name, mod_time, &size, &link))
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Scraper for the JSON listing of Caddy's `file_server browse`
+/// (answered to requests with an `Accept: application/json` header)
+#[derive(Debug)]
+pub(crate) struct CaddyJsonScraper;
+
+impl Scraper for CaddyJsonScraper {
+    fn name(&self) -> &'static str {
+        "caddy-json"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        shape_confidence(is_caddy_json(body), false)
+    }
+
+    fn detect_with_content_type(&self, body: &str, content_type: Option<&str>) -> Confidence {
+        shape_confidence(is_caddy_json(body), is_content_type(content_type, &["application/json"]))
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Caddy JSON listing detected");
+        scrape_caddy_json(body)
+    }
+}
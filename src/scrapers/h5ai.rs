@@ -1,9 +1,40 @@
 use crate::{
+    detect::detect_h5ai,
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
     scrape::scrape_table,
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
 };
 use scraper::{Html, Selector};
+use tracing::info;
+
+/// Scraper for h5ai powered websites (<https://github.com/lrsjng/h5ai>)
+#[derive(Debug)]
+pub(crate) struct H5aiScraper;
+
+impl Scraper for H5aiScraper {
+    fn name(&self) -> &'static str {
+        "h5ai"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_h5ai(body).is_some() {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn version(&self, body: &str) -> Option<String> {
+        detect_h5ai(body)
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        let version = detect_h5ai(body).unwrap_or_default();
+        info!("H5ai powered version {version} website detected");
+        scrape_h5ai(body, &version)
+    }
+}
 
 // when getting the website's htwl we get a version with
 // a fallback because Reqwest does not understand javascript
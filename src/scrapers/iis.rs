@@ -0,0 +1,100 @@
+use crate::{
+    detect::detect_iis,
+    error::{Result, SelectorResultExt},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
+};
+use scraper::{ElementRef, Html, Node, Selector};
+use tracing::{info, trace};
+
+/// Scraper for Microsoft IIS directory browsing
+/// (<https://learn.microsoft.com/en-us/iis/configuration/system.webserver/directorybrowse>)
+#[derive(Debug)]
+pub(crate) struct IisScraper;
+
+impl Scraper for IisScraper {
+    fn name(&self) -> &'static str {
+        "iis"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_iis(body) {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("IIS directory browsing detected");
+        scrape_iis(body)
+    }
+}
+
+// Returns the entry of a line whose text before the link is `text`:
+// the date followed by the size or the `<dir>` marker of directories.
+// The "[To Parent Directory]" link is alone on its line.
+fn build_iis_entry(text: &str, name: &str, link: &str) -> HttpDirectoryEntry {
+    let mut columns: Vec<&str> = text.split_whitespace().collect();
+    trace!("columns: {columns:?}, name: {name}, link: {link}");
+
+    match columns.pop() {
+        None => HttpDirectoryEntry::ParentDirectory(link.to_string()),
+        Some(marker) if marker.eq_ignore_ascii_case("<dir>") => {
+            HttpDirectoryEntry::new(&format!("{name}/"), &columns.join(" "), "-", link)
+        }
+        Some(size) => HttpDirectoryEntry::new(name, &columns.join(" "), size, link),
+    }
+}
+
+// The listing is a single <pre> block whose lines are separated by <br> tags:
+// <pre><A HREF="/">[To Parent Directory]</A><br><br>
+//  5/31/2025  1:54 PM        &lt;dir&gt; <A HREF="/pub/debian/">debian</A><br>
+//  5/31/2025  1:54 PM        12345 <A HREF="/pub/README.txt">README.txt</A><br></pre>
+// Sizes are exact byte counts and dates use the 12-hour clock
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_iis(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let html = Html::parse_document(body);
+    let pre_selector = Selector::parse("pre").with_selector("pre")?;
+    let mut entries = vec![];
+
+    for pre in html.select(&pre_selector) {
+        let mut text = String::new();
+        for node in pre.children() {
+            match node.value() {
+                Node::Text(node_text) => text.push_str(node_text),
+                Node::Element(element) if element.name() == "a" => {
+                    let link = element.attr("href").unwrap_or_default();
+                    let name = ElementRef::wrap(node).map(|a| a.text().collect::<String>()).unwrap_or_default();
+                    entries.push(build_iis_entry(&text, name.trim(), link));
+                    text.clear();
+                }
+                _ => text.clear(),
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IisScraper;
+    use crate::{
+        httpdirectoryentry::{EntryType, assert_entry},
+        scrapers::{NAMED_CONFIDENCE, Scraper},
+    };
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    #[test]
+    fn test_scrape_iis_long_dates() {
+        let body = r#"<pre><A HREF="/">[To Parent Directory]</A><br><br>Saturday, May 31, 2025 11:04 AM        &lt;dir&gt; <A HREF="/pub/debian/">debian</A><br>Monday, January 06, 2025 12:30 AM 0 <A HREF="/pub/empty">empty</A><br></pre>"#;
+
+        assert_eq!(IisScraper.detect(body), NAMED_CONFIDENCE);
+        let entries = IisScraper.scrape(body).unreachable();
+        assert_eq!(entries.len(), 3);
+        assert_entry(&entries[0], &EntryType::ParentDirectory, "/", 0, "0000-00-00 00:00");
+        assert_entry(&entries[1], &EntryType::Directory, "debian/", 0, "2025-05-31 11:04");
+        assert_entry(&entries[2], &EntryType::File, "empty", 0, "2025-01-06 00:30");
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    detect::{detect_lighttpd, detect_lighttpd_version},
+    error::{Result, SelectorResultExt},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrape::{extract_link, remove_empty_cell},
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
+};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::BTreeMap;
+use tracing::{info, trace};
+
+/// Scraper for lighttpd's `mod_dirlisting` listings
+/// (<https://redmine.lighttpd.net/projects/lighttpd/wiki/Mod_dirlisting>)
+#[derive(Debug)]
+pub(crate) struct LighttpdScraper;
+
+impl Scraper for LighttpdScraper {
+    fn name(&self) -> &'static str {
+        "lighttpd"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_lighttpd(body) {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn version(&self, body: &str) -> Option<String> {
+        detect_lighttpd_version(body)
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("lighttpd website detected");
+        scrape_lighttpd(body)
+    }
+}
+
+// Returns the text of the cell of `row` selected by `selector`
+// (`&nbsp;` and spaces trimmed)
+fn cell_text(row: ElementRef, selector: &Selector) -> String {
+    row.select(selector).next().map(|td| remove_empty_cell(td.text().collect()).concat()).unwrap_or_default()
+}
+
+// Rows of the listing look like:
+// <tr class="d"><td class="n"><a href="debian/">debian</a>/</td><td class="m">2024-Apr-05 11:59:30</td>
+//    <td class="s">- &nbsp;</td><td class="t">Directory</td></tr>
+// <tr><td class="n"><a href="README">README</a></td><td class="m">2024-Apr-05 11:59:30</td>
+//    <td class="s">4.2K</td><td class="t">text/plain</td></tr>
+// The type column is kept as the "Type" extra column of entries
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_lighttpd(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let html = Html::parse_document(body);
+    let row_selector = Selector::parse("tr").with_selector("tr")?;
+    let name_selector = Selector::parse("td.n").with_selector("td.n")?;
+    let date_selector = Selector::parse("td.m").with_selector("td.m")?;
+    let size_selector = Selector::parse("td.s").with_selector("td.s")?;
+    let type_selector = Selector::parse("td.t").with_selector("td.t")?;
+    let link_selector = Selector::parse("a").with_selector("a")?;
+
+    let entries = html
+        .select(&row_selector)
+        .filter_map(|row| {
+            let name_col = row.select(&name_selector).next()?;
+            let name = remove_empty_cell(name_col.text().collect()).concat();
+            let link = extract_link(&name_col, &link_selector);
+            let date = cell_text(row, &date_selector);
+            let size = cell_text(row, &size_selector);
+            let kind = cell_text(row, &type_selector);
+            trace!("name: {name}, link: {link}, date: {date}, size: {size}, type: {kind}");
+
+            let mut extra_columns = BTreeMap::new();
+            if !kind.is_empty() {
+                extra_columns.insert("Type".to_string(), kind);
+            }
+            Some(HttpDirectoryEntry::new(&name, &date, &size, link).with_columns(None, extra_columns))
+        })
+        .collect();
+
+    Ok(entries)
+}
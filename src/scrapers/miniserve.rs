@@ -1,10 +1,46 @@
 use crate::{
+    detect::detect_miniserve,
+    entry::UnitSystem,
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
     scrape::{are_table_headers_present, build_entry, extract_col_text, extract_link, remove_empty_cell},
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
 };
 use scraper::{ElementRef, Html, Selector};
-use tracing::trace;
+use tracing::{info, trace};
+
+/// Scraper for miniserve file servers (<https://crates.io/crates/miniserve>)
+#[derive(Debug)]
+pub(crate) struct MiniServeScraper;
+
+impl Scraper for MiniServeScraper {
+    fn name(&self) -> &'static str {
+        "miniserve"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_miniserve(body).is_some() {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn version(&self, body: &str) -> Option<String> {
+        detect_miniserve(body)
+    }
+
+    // `kB` printed by miniserve is a decimal unit (`KiB` stays binary)
+    fn unit_system(&self) -> UnitSystem {
+        UnitSystem::Si
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        let version = detect_miniserve(body).unwrap_or_default();
+        info!("Miniserve version {version} website detected");
+        scrape_miniserve(body, &version)
+    }
+}
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
 pub(crate) fn scrape_miniserve(body: &str, _version: &str) -> Result<Vec<HttpDirectoryEntry>> {
@@ -1,5 +1,421 @@
+pub(crate) mod caddy;
 pub(crate) mod h5ai;
+pub(crate) mod iis;
+pub(crate) mod lighttpd;
 pub(crate) mod miniserve;
+pub(crate) mod nginx;
+pub(crate) mod s3;
 pub(crate) mod snt;
 pub(crate) mod stil;
 pub(crate) mod ul;
+
+use crate::{
+    entry::UnitSystem,
+    error::Result,
+    httpdirectoryentry::HttpDirectoryEntry,
+    parseoptions::ParseOptions,
+    scrape::{PreScraper, TableScraper},
+    scrapers::{
+        caddy::{CaddyJsonScraper, CaddyScraper},
+        h5ai::H5aiScraper,
+        iis::IisScraper,
+        lighttpd::LighttpdScraper,
+        miniserve::MiniServeScraper,
+        nginx::{NginxJsonScraper, NginxXmlScraper},
+        s3::S3Scraper,
+        snt::SntScraper,
+        stil::StilScraper,
+        ul::UlScraper,
+    },
+};
+use std::fmt;
+use std::sync::Arc;
+use tracing::{info, warn};
+use url::Url;
+
+/// Confidence with which a `Scraper` recognizes a body: 0 means
+/// that the body is not recognized at all and 100 that it is
+/// certainly recognized
+pub type Confidence = u8;
+
+/// Confidence of scrapers that recognize a named listing software
+/// (h5ai, SNT, miniserve, Stil, Caddy, lighttpd, IIS, nginx, S3) from a
+/// signature or the shape of the body
+pub const NAMED_CONFIDENCE: Confidence = 90;
+
+// Confidence of scrapers whose recognition of the shape of the
+// body is confirmed by the content type of the response
+const CONTENT_TYPE_CONFIDENCE: Confidence = 100;
+
+// Tells whether `content_type` is one of `expected` ones
+// (parameters such as `; charset=utf-8` are ignored)
+pub(crate) fn is_content_type(content_type: Option<&str>, expected: &[&str]) -> bool {
+    content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .is_some_and(|mime| expected.iter().any(|expected| mime.trim().eq_ignore_ascii_case(expected)))
+}
+
+// Returns `body` without its leading whitespaces and
+// its XML declaration (`<?xml version="1.0"?>`) if any
+pub(crate) fn strip_xml_declaration(body: &str) -> &str {
+    let body = body.trim_start();
+    match body.strip_prefix("<?xml") {
+        Some(declaration) => declaration.split_once("?>").map_or("", |(_, rest)| rest.trim_start()),
+        None => body,
+    }
+}
+
+// Returns the local name (without any namespace prefix) of the
+// first element of `body`, an XML document, if any
+pub(crate) fn root_tag_name(body: &str) -> Option<&str> {
+    let tag = strip_xml_declaration(body).strip_prefix('<')?;
+    let name = tag.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next()?;
+    name.rsplit(':').next().filter(|name| !name.is_empty())
+}
+
+// Confidence of a scraper that recognized the shape of a JSON
+// or XML body (`shape`) that may be confirmed by the content type
+pub(crate) fn shape_confidence(shape: bool, content_type_matches: bool) -> Confidence {
+    match (shape, content_type_matches) {
+        (true, true) => CONTENT_TYPE_CONFIDENCE,
+        (true, false) => NAMED_CONFIDENCE,
+        (false, _) => 0,
+    }
+}
+
+/// A `Scraper` understands one listing format: it tells whether
+/// it recognizes a body and then scrapes it into entries.
+///
+/// ```rust
+///   use httpdirectory::{error::Result, httpdirectoryentry::HttpDirectoryEntry};
+///   use httpdirectory::scrapers::{Confidence, Scraper};
+///
+///   #[derive(Debug)]
+///   struct CsvScraper;
+///
+///   impl Scraper for CsvScraper {
+///       fn name(&self) -> &str {
+///           "csv"
+///       }
+///
+///       fn detect(&self, body: &str) -> Confidence {
+///           if body.starts_with("name,date,size") { 100 } else { 0 }
+///       }
+///
+///       fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+///           Ok(body
+///               .lines()
+///               .skip(1)
+///               .filter_map(|line| {
+///                   let columns: Vec<&str> = line.split(',').collect();
+///                   (columns.len() == 3).then(|| HttpDirectoryEntry::new(columns[0], columns[1], columns[2], columns[0]))
+///               })
+///               .collect())
+///       }
+///   }
+/// ```
+pub trait Scraper: fmt::Debug + Send + Sync {
+    /// Short name of the listing format understood by this scraper
+    fn name(&self) -> &str;
+
+    /// Tells how confident this scraper is that it understands `body`
+    fn detect(&self, body: &str) -> Confidence;
+
+    /// Tells how confident this scraper is that it understands `body`
+    /// served with the `content_type` header of the response (when it
+    /// is known). Defaults to `detect(body)` whatever the content type is.
+    fn detect_with_content_type(&self, body: &str, _content_type: Option<&str>) -> Confidence {
+        self.detect(body)
+    }
+
+    /// Returns the version of the listing software found in `body`
+    /// if any. Defaults to `None`.
+    fn version(&self, _body: &str) -> Option<String> {
+        None
+    }
+
+    /// Returns the unit system used by the listing software to print
+    /// sizes with ambiguous units (`k`, `kB`, `M`, ...). Defaults to
+    /// binary units (`UnitSystem::Iec`).
+    fn unit_system(&self) -> UnitSystem {
+        UnitSystem::Iec
+    }
+
+    /// Returns the url against which the links of the entries scraped
+    /// from the listing of `url` are resolved. Defaults to `url`.
+    fn base_url(&self, url: &Url) -> Url {
+        url.clone()
+    }
+
+    /// Returns the url of the next page of `body`, the listing of `url`,
+    /// when the listing software splits long listings into pages (S3
+    /// buckets answer at most 1000 keys at once). The entries of the
+    /// next pages are appended to the ones of the first page.
+    /// Defaults to `None`.
+    fn next_page_url(&self, _url: &Url, _body: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns the url of the listing of `dir` relative to `url`, a
+    /// listing understood by this scraper, when it is not obtained by
+    /// joining `dir` to `url` (S3 buckets list directories by prefix).
+    /// Used by `HttpDirectory::cd()`. Defaults to `None`.
+    fn directory_url(&self, _url: &Url, _dir: &str) -> Option<Url> {
+        None
+    }
+
+    /// Scrapes `body` into a vector of `HttpDirectoryEntry`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `body` could not be scraped
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>>;
+}
+
+/// Registry of scrapers used to scrape a body: the scraper with
+/// the highest confidence wins. When two scrapers have the same
+/// confidence the first registered one wins. User scrapers are
+/// always placed before the built-in ones.
+#[derive(Debug, Clone)]
+pub struct ScraperRegistry {
+    scrapers: Vec<Arc<dyn Scraper>>,
+    user_scrapers: usize,
+}
+
+// Registry with built-in scrapers only
+#[cfg(any(test, feature = "test-helpers"))]
+static BUILTIN_REGISTRY: std::sync::LazyLock<ScraperRegistry> = std::sync::LazyLock::new(ScraperRegistry::default);
+
+impl Default for ScraperRegistry {
+    /// Returns a registry with all built-in scrapers (h5ai, SNT,
+    /// miniserve, Stil, Caddy HTML and JSON, lighttpd, IIS, nginx
+    /// JSON and XML, S3 buckets, table, pre and ul)
+    fn default() -> Self {
+        ScraperRegistry {
+            scrapers: vec![
+                Arc::new(H5aiScraper),
+                Arc::new(SntScraper),
+                Arc::new(MiniServeScraper),
+                Arc::new(StilScraper),
+                Arc::new(CaddyScraper),
+                Arc::new(CaddyJsonScraper),
+                Arc::new(LighttpdScraper),
+                Arc::new(IisScraper),
+                Arc::new(NginxJsonScraper),
+                Arc::new(NginxXmlScraper),
+                Arc::new(S3Scraper),
+                Arc::new(TableScraper),
+                Arc::new(PreScraper),
+                Arc::new(UlScraper),
+            ],
+            user_scrapers: 0,
+        }
+    }
+}
+
+impl ScraperRegistry {
+    /// Returns a registry with all built-in scrapers
+    #[must_use]
+    pub fn new() -> Self {
+        ScraperRegistry::default()
+    }
+
+    /// Returns a registry without any scraper
+    #[must_use]
+    pub fn empty() -> Self {
+        ScraperRegistry {
+            scrapers: vec![],
+            user_scrapers: 0,
+        }
+    }
+
+    /// Registers `scraper` after previously registered user
+    /// scrapers and before built-in ones
+    pub fn register<S: Scraper + 'static>(&mut self, scraper: S) -> &mut Self {
+        self.scrapers.insert(self.user_scrapers, Arc::new(scraper));
+        self.user_scrapers += 1;
+        self
+    }
+
+    /// Returns the names of the registered scrapers in priority order
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.scrapers.iter().map(|scraper| scraper.name()).collect()
+    }
+
+    // Returns the first registered scraper named `name` if any
+    pub(crate) fn get(&self, name: &str) -> Option<&dyn Scraper> {
+        self.scrapers.iter().find(|scraper| scraper.name() == name).map(Arc::as_ref)
+    }
+
+    /// Returns the scraper with the highest confidence for `body`
+    /// if any scraper recognizes it
+    #[must_use]
+    pub fn detect(&self, body: &str) -> Option<&dyn Scraper> {
+        self.detect_with_content_type(body, None)
+    }
+
+    /// Returns the scraper with the highest confidence for `body`
+    /// served with `content_type` (the `Content-Type` header of the
+    /// response when it is known) if any scraper recognizes it
+    #[cfg_attr(feature = "hotpath", hotpath::measure)]
+    #[must_use]
+    pub fn detect_with_content_type(&self, body: &str, content_type: Option<&str>) -> Option<&dyn Scraper> {
+        let mut best: Option<(&dyn Scraper, Confidence)> = None;
+
+        for scraper in &self.scrapers {
+            let confidence = scraper.detect_with_content_type(body, content_type);
+            if confidence > 0 && best.is_none_or(|(_, best_confidence)| confidence > best_confidence) {
+                best = Some((scraper.as_ref(), confidence));
+            }
+        }
+
+        best.map(|(scraper, _)| scraper)
+    }
+
+    /// Parses `body` with the scraper that recognizes it best to
+    /// find entries of files, directories or a parent directory
+    /// (sizes are read with the unit system of that scraper and dates
+    /// are parsed with `options`).
+    /// Returns an empty vector when no scraper recognizes `body`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected scraper fails
+    pub fn scrape(&self, body: &str, options: &ParseOptions) -> Result<Vec<HttpDirectoryEntry>> {
+        if let Some(scraper) = self.detect(body) {
+            info!("{} listing detected", scraper.name());
+            let mut entries = scraper.scrape(body)?;
+            options.complete_entries(scraper, &mut entries);
+            Ok(entries)
+        } else {
+            warn!("Site type has not been detected: doing nothing");
+            Ok(vec![])
+        }
+    }
+
+    // Returns the registry with built-in scrapers only
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub(crate) fn builtin() -> &'static ScraperRegistry {
+        &BUILTIN_REGISTRY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confidence, Scraper, ScraperRegistry, is_content_type};
+    use crate::{entry::UnitSystem, error::Result, httpdirectoryentry::HttpDirectoryEntry, parseoptions::ParseOptions};
+
+    #[derive(Debug)]
+    struct TestScraper(&'static str, Confidence);
+
+    impl Scraper for TestScraper {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn detect(&self, _body: &str) -> Confidence {
+            self.1
+        }
+
+        fn scrape(&self, _body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+            Ok(vec![HttpDirectoryEntry::new(self.0, "", "12", self.0)])
+        }
+    }
+
+    #[test]
+    fn test_is_content_type() {
+        assert!(is_content_type(Some("application/json; charset=utf-8"), &["application/json"]));
+        assert!(is_content_type(Some("Text/XML"), &["application/xml", "text/xml"]));
+        assert!(!is_content_type(Some("text/html"), &["application/json"]));
+        assert!(!is_content_type(None, &["application/json"]));
+    }
+
+    #[test]
+    fn test_registry_names() {
+        let mut registry = ScraperRegistry::new();
+        registry.register(TestScraper("first", 0)).register(TestScraper("second", 0));
+
+        assert_eq!(
+            registry.names(),
+            vec![
+                "first",
+                "second",
+                "h5ai",
+                "snt",
+                "miniserve",
+                "stil",
+                "caddy",
+                "caddy-json",
+                "lighttpd",
+                "iis",
+                "nginx-json",
+                "nginx-xml",
+                "s3",
+                "table",
+                "pre",
+                "ul"
+            ]
+        );
+        assert!(ScraperRegistry::empty().names().is_empty());
+    }
+
+    #[test]
+    fn test_registry_user_scraper_priority() {
+        let body = "<table><tr><th>Name</th><th>Last modified</th><th>Size</th></tr></table>";
+        let mut registry = ScraperRegistry::new();
+        assert_eq!(registry.detect(body).map(Scraper::name), Some("table"));
+
+        registry.register(TestScraper("lower", 10));
+        assert_eq!(registry.detect(body).map(Scraper::name), Some("table"));
+
+        // Same confidence than the table scraper: the user scraper wins
+        registry.register(TestScraper("equal", 60));
+        assert_eq!(registry.detect(body).map(Scraper::name), Some("equal"));
+
+        let entries = registry.scrape(body, &ParseOptions::default()).unwrap_or_default();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name(), Some("equal"));
+    }
+
+    #[derive(Debug)]
+    struct SiScraper;
+
+    impl Scraper for SiScraper {
+        fn name(&self) -> &'static str {
+            "si"
+        }
+
+        fn detect(&self, _body: &str) -> Confidence {
+            100
+        }
+
+        fn unit_system(&self) -> UnitSystem {
+            UnitSystem::Si
+        }
+
+        fn scrape(&self, _body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+            Ok(vec![HttpDirectoryEntry::new("file", "", "1.5 kB", "file")])
+        }
+    }
+
+    #[test]
+    fn test_registry_unit_system() {
+        let mut registry = ScraperRegistry::empty();
+        registry.register(SiScraper);
+
+        let entries = registry.scrape("", &ParseOptions::default()).unwrap_or_default();
+        assert_eq!(entries.len(), 1);
+        match &entries[0] {
+            HttpDirectoryEntry::File(entry) => assert_eq!(entry.size(), 1500),
+            other => panic!("Unexpected entry {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_registry_nothing_detected() {
+        let registry = ScraperRegistry::empty();
+        assert!(registry.detect("<ul></ul>").is_none());
+        assert!(registry.scrape("<ul></ul>", &ParseOptions::default()).unwrap_or_default().is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    error::{JsonResultExt, Result, XmlResultExt},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrape::link_from_name,
+    scrapers::{Confidence, Scraper, is_content_type, root_tag_name, shape_confidence},
+};
+use roxmltree::{Document, Node};
+use serde_json::Value;
+use tracing::{info, trace};
+
+// Returns the entry of a directory, a file or something else
+// (`other` type of nginx, treated as a file) named `name`
+fn build_autoindex_entry(kind: &str, name: &str, mtime: &str, size: Option<&str>) -> HttpDirectoryEntry {
+    trace!("type: {kind}, name: {name}, mtime: {mtime}, size: {size:?}");
+    if kind == "directory" {
+        let name = format!("{name}/");
+        HttpDirectoryEntry::new(&name, mtime, "-", &link_from_name(&name))
+    } else {
+        HttpDirectoryEntry::new(name, mtime, size.unwrap_or_default(), &link_from_name(name))
+    }
+}
+
+// Tells whether `body` is a JSON array of objects that all have
+// a `name` and a `type` (an empty array is an empty directory)
+fn is_json_autoindex(body: &str) -> bool {
+    if !body.trim_start().starts_with('[') {
+        return false;
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(items)) => items.iter().all(|item| {
+            item.get("name").is_some_and(Value::is_string) && item.get("type").is_some_and(Value::is_string)
+        }),
+        _ => false,
+    }
+}
+
+// Parses the JSON output of nginx autoindex module:
+// [ { "name":"dir", "type":"directory", "mtime":"Wed, 21 Oct 2015 07:28:00 GMT" },
+//   { "name":"file", "type":"file", "mtime":"Wed, 21 Oct 2015 07:28:00 GMT", "size":1234 } ]
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_nginx_json(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let items: Vec<Value> = serde_json::from_str(body).with_json()?;
+
+    let entries = items
+        .iter()
+        .filter_map(|item| {
+            let name = item.get("name")?.as_str()?;
+            let kind = item.get("type").and_then(Value::as_str).unwrap_or("file");
+            let mtime = item.get("mtime").and_then(Value::as_str).unwrap_or_default();
+            let size = item.get("size").and_then(Value::as_u64).map(|size| size.to_string());
+            Some(build_autoindex_entry(kind, name, mtime, size.as_deref()))
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Scraper for nginx autoindex module with `autoindex_format json;`
+#[derive(Debug)]
+pub(crate) struct NginxJsonScraper;
+
+impl Scraper for NginxJsonScraper {
+    fn name(&self) -> &'static str {
+        "nginx-json"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        shape_confidence(is_json_autoindex(body), false)
+    }
+
+    fn detect_with_content_type(&self, body: &str, content_type: Option<&str>) -> Confidence {
+        shape_confidence(is_json_autoindex(body), is_content_type(content_type, &["application/json"]))
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("nginx JSON autoindex detected");
+        scrape_nginx_json(body)
+    }
+}
+
+// Tells whether `body` is a `<list>` XML document
+fn is_xml_autoindex(body: &str) -> bool {
+    root_tag_name(body) == Some("list") && body.trim_end().ends_with("</list>")
+}
+
+// Parses the XML output of nginx autoindex module:
+// <list>
+//   <directory mtime="2015-10-21T07:28:00Z">dir</directory>
+//   <file mtime="2015-10-21T07:28:00Z" size="1234">file</file>
+// </list>
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_nginx_xml(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let document = Document::parse(body).with_xml()?;
+
+    let entries = document
+        .root_element()
+        .children()
+        .filter(|item| ["directory", "file", "other"].iter().any(|kind| item.has_tag_name(*kind)))
+        .map(|item: Node| {
+            let name = item.text().unwrap_or_default();
+            let mtime = item.attribute("mtime").unwrap_or_default();
+            build_autoindex_entry(item.tag_name().name(), name, mtime, item.attribute("size"))
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Scraper for nginx autoindex module with `autoindex_format xml;`
+#[derive(Debug)]
+pub(crate) struct NginxXmlScraper;
+
+impl Scraper for NginxXmlScraper {
+    fn name(&self) -> &'static str {
+        "nginx-xml"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        shape_confidence(is_xml_autoindex(body), false)
+    }
+
+    fn detect_with_content_type(&self, body: &str, content_type: Option<&str>) -> Confidence {
+        shape_confidence(is_xml_autoindex(body), is_content_type(content_type, &["application/xml", "text/xml"]))
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("nginx XML autoindex detected");
+        scrape_nginx_xml(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NginxJsonScraper, NginxXmlScraper};
+    use crate::{
+        httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+        scrapers::{NAMED_CONFIDENCE, Scraper},
+    };
+    use unwrap_unreachable::UnwrapUnreachable;
+
+    const JSON_BODY: &str = r#"[
+{ "name":"debian", "type":"directory", "mtime":"Wed, 21 Oct 2015 07:28:00 GMT" },
+{ "name":"SHA256SUMS", "type":"file", "mtime":"Sat, 05 Apr 2025 11:59:30 GMT", "size":12345678901 },
+{ "name":"a #1.iso", "type":"file", "mtime":"Sat, 05 Apr 2025 11:59:30 GMT", "size":10 }
+]
+"#;
+
+    const XML_BODY: &str = r#"<?xml version="1.0"?>
+<list>
+<directory mtime="2015-10-21T07:28:00Z">debian</directory>
+<file mtime="2025-04-05T11:59:30Z" size="12345678901">SHA256SUMS</file>
+<file mtime="2025-04-05T11:59:30Z" size="10">a&amp;b.iso</file>
+</list>
+"#;
+
+    fn file_size(entry: &HttpDirectoryEntry) -> Option<u64> {
+        match entry {
+            HttpDirectoryEntry::File(entry) => entry.exact_size(),
+            HttpDirectoryEntry::Directory(_) | HttpDirectoryEntry::ParentDirectory(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_detect_nginx_json() {
+        assert_eq!(NginxJsonScraper.detect(JSON_BODY), NAMED_CONFIDENCE);
+        assert_eq!(NginxJsonScraper.detect_with_content_type(JSON_BODY, Some("application/json")), 100);
+        assert_eq!(NginxJsonScraper.detect("[\n]"), NAMED_CONFIDENCE);
+        assert_eq!(NginxJsonScraper.detect(r#"[{"name":"a","size":1}]"#), 0);
+        assert_eq!(NginxJsonScraper.detect_with_content_type("<ul></ul>", Some("application/json")), 0);
+        assert_eq!(NginxXmlScraper.detect(JSON_BODY), 0);
+    }
+
+    #[test]
+    fn test_scrape_nginx_json() {
+        let entries = NginxJsonScraper.scrape(JSON_BODY).unreachable();
+        assert_eq!(entries.len(), 3);
+
+        assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "2015-10-21 07:28");
+        assert_entry(&entries[1], &EntryType::File, "SHA256SUMS", 12_345_678_901, "2025-04-05 11:59");
+        assert_eq!(file_size(&entries[1]), Some(12_345_678_901));
+        assert_eq!(entries[1].date().map(|date| date.to_rfc3339()), Some("2025-04-05T11:59:30+00:00".to_string()));
+        match &entries[2] {
+            HttpDirectoryEntry::File(entry) => {
+                assert_eq!(entry.name(), "a #1.iso");
+                assert_eq!(entry.link(), "a %231.iso");
+                assert!(entry.date_has_offset());
+            }
+            other => panic!("Unexpected entry {other:?}"),
+        }
+
+        assert!(NginxJsonScraper.scrape("[{").is_err());
+    }
+
+    #[test]
+    fn test_detect_nginx_xml() {
+        assert_eq!(NginxXmlScraper.detect(XML_BODY), NAMED_CONFIDENCE);
+        assert_eq!(NginxXmlScraper.detect_with_content_type(XML_BODY, Some("text/xml")), 100);
+        assert_eq!(NginxXmlScraper.detect("<list>\n</list>"), NAMED_CONFIDENCE);
+        assert_eq!(NginxXmlScraper.detect("<html><list></list></html>"), 0);
+        assert_eq!(NginxJsonScraper.detect(XML_BODY), 0);
+    }
+
+    #[test]
+    fn test_scrape_nginx_xml() {
+        let entries = NginxXmlScraper.scrape(XML_BODY).unreachable();
+        assert_eq!(entries.len(), 3);
+
+        assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "2015-10-21 07:28");
+        assert_entry(&entries[1], &EntryType::File, "SHA256SUMS", 12_345_678_901, "2025-04-05 11:59");
+        assert_eq!(file_size(&entries[1]), Some(12_345_678_901));
+        assert_eq!(entries[1].date().map(|date| date.to_rfc3339()), Some("2025-04-05T11:59:30+00:00".to_string()));
+        assert_eq!(entries[2].name(), Some("a&b.iso"));
+
+        let body = "<list><file mtime=\"2025-04-05T11:59:30Z\" size=\"1\"><![CDATA[<a>.iso]]></file><other/></list>";
+        assert_eq!(NginxXmlScraper.detect(body), NAMED_CONFIDENCE);
+        let entries = NginxXmlScraper.scrape(body).unreachable();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name(), Some("<a>.iso"));
+        assert!(NginxXmlScraper.scrape("<list><file></list>").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    error::{Result, XmlResultExt},
+    httpdirectoryentry::HttpDirectoryEntry,
+    scrape::link_from_name,
+    scrapers::{Confidence, Scraper, is_content_type, root_tag_name, shape_confidence},
+};
+use roxmltree::{Document, Node};
+use std::collections::HashSet;
+use tracing::{info, trace};
+use url::{Url, form_urlencoded};
+
+// An object (a file) of a bucket
+struct S3Object {
+    key: String,
+    last_modified: String,
+    size: String,
+}
+
+// A page of a `ListBucketResult` listing (ListObjects or ListObjectsV2)
+#[derive(Default)]
+struct ListBucketResult {
+    prefix: String,
+    contents: Vec<S3Object>,
+    common_prefixes: Vec<String>,
+    is_truncated: bool,
+    next_continuation_token: Option<String>,
+    next_marker: Option<String>,
+}
+
+// Returns the trimmed text of `element` (empty for `<Marker/>`)
+fn text(element: Node) -> String {
+    element.text().unwrap_or_default().trim().to_string()
+}
+
+// Returns the text of the first child element of `parent` named
+// `name` (whatever its namespace is)
+fn child_text(parent: Node, name: &str) -> Option<String> {
+    parent.children().find(|child| child.has_tag_name(name)).map(text)
+}
+
+impl ListBucketResult {
+    // Parses a page that looks like:
+    // <ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    //   <Name>mirror</Name><Prefix>pub/</Prefix><Delimiter>/</Delimiter>
+    //   <IsTruncated>true</IsTruncated><NextContinuationToken>1ueGcxL</NextContinuationToken>
+    //   <Contents><Key>pub/README.txt</Key><LastModified>2025-05-31T13:54:45.000Z</LastModified>
+    //     <ETag>"a8c5..."</ETag><Size>12345</Size><StorageClass>STANDARD</StorageClass></Contents>
+    //   <CommonPrefixes><Prefix>pub/debian/</Prefix></CommonPrefixes>
+    // </ListBucketResult>
+    // Elements are matched by their local name so that a namespace
+    // prefix (`<s3:Contents>`) does not matter.
+    fn parse(body: &str) -> Result<Self> {
+        let document = Document::parse(body).with_xml()?;
+        let mut listing = ListBucketResult::default();
+
+        for element in document.root_element().children().filter(Node::is_element) {
+            match element.tag_name().name() {
+                "Prefix" => listing.prefix = text(element),
+                "Contents" => {
+                    if let Some(key) = child_text(element, "Key") {
+                        listing.contents.push(S3Object {
+                            key,
+                            last_modified: child_text(element, "LastModified").unwrap_or_default(),
+                            size: child_text(element, "Size").unwrap_or_default(),
+                        });
+                    }
+                }
+                "CommonPrefixes" => listing.common_prefixes.extend(child_text(element, "Prefix")),
+                "IsTruncated" => listing.is_truncated = text(element) == "true",
+                "NextContinuationToken" => listing.next_continuation_token = Some(text(element)),
+                "NextMarker" => listing.next_marker = Some(text(element)),
+                _ => (),
+            }
+        }
+
+        listing.next_continuation_token.take_if(|token| token.is_empty());
+        listing.next_marker.take_if(|marker| marker.is_empty());
+        Ok(listing)
+    }
+}
+
+// Query of the listing of the directory `prefix` with the
+// ListObjectsV2 API and `/` as delimiter
+fn prefix_query(prefix: &str) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .append_pair("list-type", "2")
+        .append_pair("prefix", prefix)
+        .append_pair("delimiter", "/")
+        .finish()
+}
+
+// Returns the prefix of `dir` relative to the directory `prefix`:
+// `..` goes to the parent directory and a leading `/` to the root
+// of the bucket. Non empty prefixes end with a `/`.
+fn join_prefix(prefix: &str, dir: &str) -> String {
+    let mut parts: Vec<&str> = if dir.starts_with('/') {
+        vec![]
+    } else {
+        prefix.split('/').filter(|part| !part.is_empty()).collect()
+    };
+
+    for part in dir.split('/') {
+        match part {
+            "" | "." => (),
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    parts.iter().fold(String::new(), |mut joined, part| {
+        joined.push_str(part);
+        joined.push('/');
+        joined
+    })
+}
+
+// Returns `url` with its `name` query parameter set to `value`
+fn with_query_pair(url: &Url, name: &str, value: &str) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs).append_pair(name, value);
+    url
+}
+
+// Tells whether `body` is a `ListBucketResult` XML document
+fn is_list_bucket_result(body: &str) -> bool {
+    root_tag_name(body) == Some("ListBucketResult")
+}
+
+// Directories are the common prefixes and, when the bucket has been
+// listed without any delimiter, the first level of the keys found
+// below the prefix. Their links are the listings of their prefix and
+// the links of files are their keys, both relative to the bucket url.
+#[cfg_attr(feature = "hotpath", hotpath::measure)]
+pub(crate) fn scrape_s3(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+    let listing = ListBucketResult::parse(body)?;
+    let mut entries = vec![];
+
+    let nested = listing.contents.iter().filter_map(|object| {
+        let (directory, _) = object.key.strip_prefix(&listing.prefix)?.split_once('/')?;
+        Some(format!("{}{directory}/", listing.prefix))
+    });
+    let mut seen = HashSet::new();
+    for prefix in listing.common_prefixes.iter().cloned().chain(nested) {
+        if let Some(name) = prefix.strip_prefix(&listing.prefix)
+            && !name.is_empty()
+            && seen.insert(prefix.clone())
+        {
+            trace!("prefix: {prefix}");
+            entries.push(HttpDirectoryEntry::new(name, "", "-", &format!("./?{}", prefix_query(&prefix))));
+        }
+    }
+
+    for object in &listing.contents {
+        match object.key.strip_prefix(&listing.prefix) {
+            Some(name) if !name.is_empty() && !name.contains('/') => {
+                trace!("key: {}, date: {}, size: {}", object.key, object.last_modified, object.size);
+                let link = format!("./{}", link_from_name(&object.key));
+                entries.push(HttpDirectoryEntry::new(name, &object.last_modified, &object.size, &link));
+            }
+            _ => trace!("Not listing key {} here", object.key),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Scraper for the `ListBucketResult` XML listing of Amazon S3
+/// compatible buckets (AWS, `MinIO`, Ceph, ...). Prefixes are
+/// directories and keys are files with their exact size.
+#[derive(Debug)]
+pub(crate) struct S3Scraper;
+
+impl Scraper for S3Scraper {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        shape_confidence(is_list_bucket_result(body), false)
+    }
+
+    fn detect_with_content_type(&self, body: &str, content_type: Option<&str>) -> Confidence {
+        shape_confidence(is_list_bucket_result(body), is_content_type(content_type, &["application/xml", "text/xml"]))
+    }
+
+    // ListObjectsV2 pages are continued with their token and
+    // ListObjects ones with a marker (the last key when the
+    // listing has been requested without any delimiter)
+    fn next_page_url(&self, url: &Url, body: &str) -> Option<Url> {
+        let listing = ListBucketResult::parse(body).ok()?;
+        if !listing.is_truncated {
+            return None;
+        }
+
+        if let Some(token) = listing.next_continuation_token {
+            Some(with_query_pair(url, "continuation-token", &token))
+        } else {
+            let marker = listing.next_marker.or_else(|| listing.contents.last().map(|object| object.key.clone()))?;
+            Some(with_query_pair(url, "marker", &marker))
+        }
+    }
+
+    // Links are relative to the bucket url: a path style bucket
+    // url may have been given without its trailing `/`
+    fn base_url(&self, url: &Url) -> Url {
+        let mut url = url.clone();
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+        url
+    }
+
+    fn directory_url(&self, url: &Url, dir: &str) -> Option<Url> {
+        let prefix = url.query_pairs().find(|(name, _)| name == "prefix").map(|(_, value)| value.into_owned());
+        let mut url = self.base_url(url);
+        url.set_query(Some(&prefix_query(&join_prefix(&prefix.unwrap_or_default(), dir))));
+        Some(url)
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("S3 bucket listing detected");
+        scrape_s3(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{S3Scraper, join_prefix};
+    use crate::{
+        httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+        scrapers::{NAMED_CONFIDENCE, Scraper},
+    };
+    use unwrap_unreachable::UnwrapUnreachable;
+    use url::Url;
+
+    // ListObjects (V1) answer to a GET of the bucket without any delimiter
+    const FLAT_BODY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><Name>mirror</Name><Prefix></Prefix><Marker/><MaxKeys>2</MaxKeys><IsTruncated>true</IsTruncated>
+<Contents><Key>README.txt</Key><LastModified>2025-05-31T13:54:45.000Z</LastModified><ETag>&quot;a8c5&quot;</ETag><Size>12345</Size><StorageClass>STANDARD</StorageClass></Contents>
+<Contents><Key>debian/dists/Release</Key><LastModified>2025-05-30T08:00:00.000Z</LastModified><ETag>&quot;b9d6&quot;</ETag><Size>42</Size><StorageClass>STANDARD</StorageClass></Contents>
+</ListBucketResult>"#;
+
+    // ListObjectsV2 answer with a namespace prefix, CDATA sections
+    // and self-closed elements
+    const NAMESPACED_BODY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<s3:ListBucketResult xmlns:s3="http://s3.amazonaws.com/doc/2006-03-01/"><s3:Name>mirror</s3:Name><s3:Prefix>pub/</s3:Prefix><s3:NextContinuationToken/><s3:IsTruncated>false</s3:IsTruncated>
+<s3:Contents/>
+<s3:Contents><s3:Key><![CDATA[pub/a&b <1>.txt]]></s3:Key><s3:LastModified>2025-05-31T13:54:45.000Z</s3:LastModified><s3:ETag/><s3:Size>7</s3:Size></s3:Contents>
+<s3:CommonPrefixes/>
+<s3:CommonPrefixes><s3:Prefix>pub/debian/</s3:Prefix></s3:CommonPrefixes>
+</s3:ListBucketResult>"#;
+
+    #[test]
+    fn test_detect_s3() {
+        assert_eq!(S3Scraper.detect(FLAT_BODY), NAMED_CONFIDENCE);
+        assert_eq!(S3Scraper.detect(NAMESPACED_BODY), NAMED_CONFIDENCE);
+        assert_eq!(S3Scraper.detect_with_content_type(FLAT_BODY, Some("application/xml")), 100);
+        assert_eq!(S3Scraper.detect("<list>\n</list>"), 0);
+        assert_eq!(S3Scraper.detect("<ListBucketResultOther/>"), 0);
+    }
+
+    #[test]
+    fn test_scrape_s3_namespaced() {
+        let entries = S3Scraper.scrape(NAMESPACED_BODY).unreachable();
+        assert_eq!(entries.len(), 2);
+        assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "0000-00-00 00:00");
+        assert_entry(&entries[1], &EntryType::File, "a&b <1>.txt", 7, "2025-05-31 13:54");
+
+        let url = Url::parse("http://localhost:9000/mirror/").unreachable();
+        assert_eq!(S3Scraper.next_page_url(&url, NAMESPACED_BODY), None);
+
+        match S3Scraper.scrape("<ListBucketResult><Contents></ListBucketResult>") {
+            Ok(_) => panic!("This test should return Err()"),
+            Err(e) => assert!(e.to_string().starts_with("Error while parsing XML listing:")),
+        }
+    }
+
+    #[test]
+    fn test_scrape_s3_without_delimiter() {
+        let entries = S3Scraper.scrape(FLAT_BODY).unreachable();
+        assert_eq!(entries.len(), 2);
+        assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "0000-00-00 00:00");
+        assert_entry(&entries[1], &EntryType::File, "README.txt", 12_345, "2025-05-31 13:54");
+        match &entries[0] {
+            HttpDirectoryEntry::Directory(entry) => {
+                assert_eq!(entry.link(), "./?list-type=2&prefix=debian%2F&delimiter=%2F");
+            }
+            other => panic!("Unexpected entry {other:?}"),
+        }
+
+        let url = Url::parse("http://localhost:9000/mirror/").unreachable();
+        let next = S3Scraper.next_page_url(&url, FLAT_BODY).map(|url| url.to_string());
+        assert_eq!(next.as_deref(), Some("http://localhost:9000/mirror/?marker=debian%2Fdists%2FRelease"));
+        assert_eq!(S3Scraper.next_page_url(&url, &FLAT_BODY.replace(">true<", ">false<")), None);
+    }
+
+    #[test]
+    fn test_s3_directory_url() {
+        assert_eq!(join_prefix("pub/", "debian/"), "pub/debian/");
+        assert_eq!(join_prefix("pub/debian/", "../ubuntu"), "pub/ubuntu/");
+        assert_eq!(join_prefix("pub/debian/", "/"), "");
+        assert_eq!(join_prefix("", "./pub/debian"), "pub/debian/");
+
+        let url =
+            Url::parse("http://localhost:9000/mirror?list-type=2&prefix=pub%2F&continuation-token=abc").unreachable();
+        let url = S3Scraper.directory_url(&url, "debian/").map(|url| url.to_string());
+        assert_eq!(
+            url.as_deref(),
+            Some("http://localhost:9000/mirror/?list-type=2&prefix=pub%2Fdebian%2F&delimiter=%2F")
+        );
+    }
+}
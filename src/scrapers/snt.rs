@@ -1,10 +1,35 @@
 use crate::{
+    detect::detect_snt,
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
     scrape::scrape_table,
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
 };
 use scraper::{Html, Selector};
-use tracing::trace;
+use tracing::{info, trace};
+
+/// Scraper for SNT index generator websites (<https://snt.utwente.nl/en/>)
+#[derive(Debug)]
+pub(crate) struct SntScraper;
+
+impl Scraper for SntScraper {
+    fn name(&self) -> &'static str {
+        "snt"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_snt(body) {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("SNT index generator website detected");
+        scrape_snt(body)
+    }
+}
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
 pub(crate) fn scrape_snt(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
@@ -1,9 +1,34 @@
 use crate::{
+    detect::detect_stil,
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
+    scrapers::{Confidence, NAMED_CONFIDENCE, Scraper},
 };
 use scraper::{Html, Selector};
-use tracing::trace;
+use tracing::{info, trace};
+
+/// Scraper for stil static index listings (<https://crates.io/crates/stil>)
+#[derive(Debug)]
+pub(crate) struct StilScraper;
+
+impl Scraper for StilScraper {
+    fn name(&self) -> &'static str {
+        "stil"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if detect_stil(body) {
+            NAMED_CONFIDENCE
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Stil STatic Index List website detected");
+        scrape_stil(body)
+    }
+}
 
 fn extract<'a>(html: &'a str, opening_tag: &str, closing_tag: &str) -> (&'a str, Option<&'a str>) {
     if let Some(opening) = html.find(opening_tag) {
@@ -2,9 +2,34 @@ use crate::{
     error::{Result, SelectorResultExt},
     httpdirectoryentry::HttpDirectoryEntry,
     scrape::{build_entry, get_link_and_name},
+    scrapers::{Confidence, Scraper},
 };
 use scraper::{Html, Selector};
-use tracing::debug;
+use tracing::{debug, info};
+
+/// Scraper for pages that list entries in an `<ul>` list. It
+/// is the last resort scraper.
+#[derive(Debug)]
+pub(crate) struct UlScraper;
+
+impl Scraper for UlScraper {
+    fn name(&self) -> &'static str {
+        "ul"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if body.contains("<ul>") {
+            20
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> Result<Vec<HttpDirectoryEntry>> {
+        info!("Body has no <table>, nor <pre> but <ul> tag");
+        scrape_ul(body)
+    }
+}
 
 #[cfg_attr(feature = "hotpath", hotpath::measure)]
 pub(crate) fn scrape_ul(body: &str) -> Result<Vec<HttpDirectoryEntry>> {
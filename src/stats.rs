@@ -1,8 +1,9 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, FixedOffset};
 use std::fmt;
 
 /// Gives statistics about an `HttpDirectoryEntry`
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stats {
     /// number of parent directory (there should only be one)
     pub parent_dir: u8,
@@ -34,7 +35,7 @@ impl Stats {
         self
     }
 
-    pub(crate) fn add_directory(&mut self, dir_date: Option<NaiveDateTime>) -> &Self {
+    pub(crate) fn add_directory(&mut self, dir_date: Option<DateTime<FixedOffset>>) -> &Self {
         self.dirs += 1;
         match dir_date {
             Some(_) => self.with_date += 1,
@@ -43,9 +44,9 @@ impl Stats {
         self
     }
 
-    pub(crate) fn add_file(&mut self, file_date: Option<NaiveDateTime>, file_size: usize) -> &Self {
+    pub(crate) fn add_file(&mut self, file_date: Option<DateTime<FixedOffset>>, file_size: u64) -> &Self {
         self.files += 1;
-        self.total_size += file_size as u64;
+        self.total_size = self.total_size.saturating_add(file_size);
         match file_date {
             Some(_) => self.with_date += 1,
             None => self.without_date += 1,
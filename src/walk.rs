@@ -0,0 +1,218 @@
+use crate::{
+    conditional::ListingCache,
+    error::{ParseResultExt, Result},
+    fetcher::Fetcher,
+    httpdirectory::fetch_entries,
+    httpdirectoryentry::HttpDirectoryEntry,
+    parseoptions::ParseOptions,
+    scrapers::ScraperRegistry,
+};
+use futures::{
+    StreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream, FuturesUnordered},
+};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use tracing::{debug, trace};
+use url::Url;
+
+/// Options used by `HttpDirectory::walk()` to crawl an
+/// index tree recursively
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// Maximum depth below the starting directory (`None`
+    /// means no limit, 0 only lists the starting directory)
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of directories retrieved at the same time
+    /// (0 is read as 1)
+    pub concurrency: usize,
+
+    /// Only walks into directories hosted by the same host than
+    /// the starting directory
+    pub same_host: bool,
+}
+
+impl Default for WalkOptions {
+    /// No depth limit, 4 concurrent requests and same host restriction
+    fn default() -> Self {
+        WalkOptions {
+            max_depth: None,
+            concurrency: 4,
+            same_host: true,
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Returns default `WalkOptions`
+    #[must_use]
+    pub fn new() -> Self {
+        WalkOptions::default()
+    }
+
+    /// Sets the maximum depth below the starting directory
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum number of concurrent requests (at least 1)
+    #[must_use]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Restricts (or not) the walk to the host of the starting directory
+    #[must_use]
+    pub fn same_host(mut self, same_host: bool) -> Self {
+        self.same_host = same_host;
+        self
+    }
+}
+
+// A directory that has to be retrieved
+struct Directory {
+    url: Url,
+    path: String,
+    depth: usize,
+}
+
+type Fetched = (Directory, Result<Vec<HttpDirectoryEntry>>);
+
+struct Walker {
+    fetcher: Arc<dyn Fetcher>,
+    scrapers: Arc<ScraperRegistry>,
+    parse_options: Arc<ParseOptions>,
+    listings: Arc<ListingCache>,
+    options: WalkOptions,
+    host: Option<String>,
+    visited: HashSet<Url>,
+    pending: VecDeque<Directory>,
+    in_flight: FuturesUnordered<BoxFuture<'static, Fetched>>,
+    ready: VecDeque<Result<(String, HttpDirectoryEntry)>>,
+}
+
+impl Walker {
+    // Queues every entry of `entries` listed in `directory` to be
+    // returned and schedules sub directories to be retrieved
+    fn process(&mut self, directory: &Directory, entries: Vec<HttpDirectoryEntry>) {
+        for entry in entries {
+            let (name, url) = match &entry {
+                HttpDirectoryEntry::ParentDirectory(_) => continue,
+                HttpDirectoryEntry::File(file) => {
+                    self.ready.push_back(Ok((format!("{}{}", directory.path, file.name()), entry)));
+                    continue;
+                }
+                HttpDirectoryEntry::Directory(dir) => (dir.name().to_string(), dir.url().cloned()),
+            };
+
+            let path = format!("{}{}/", directory.path, name.trim_matches('/'));
+            self.ready.push_back(Ok((path.clone(), entry)));
+
+            if self.options.max_depth.is_some_and(|max_depth| directory.depth >= max_depth) {
+                continue;
+            }
+
+            match url {
+                Some(url) if self.options.same_host && url.host_str().map(str::to_string) != self.host => {
+                    trace!("Not walking into {url}: not the same host");
+                }
+                Some(url) => {
+                    if self.visited.insert(url.clone()) {
+                        self.pending.push_back(Directory {
+                            url,
+                            path,
+                            depth: directory.depth + 1,
+                        });
+                    } else {
+                        trace!("Not walking into {url}: already visited");
+                    }
+                }
+                None => debug!("Not walking into '{name}': its url could not be resolved"),
+            }
+        }
+    }
+
+    // Starts retrieving pending directories while
+    // the concurrency limit is not reached
+    fn start_pending(&mut self) {
+        while self.in_flight.len() < self.options.concurrency.max(1) {
+            let Some(directory) = self.pending.pop_front() else {
+                break;
+            };
+            let fetcher = Arc::clone(&self.fetcher);
+            let scrapers = Arc::clone(&self.scrapers);
+            let parse_options = Arc::clone(&self.parse_options);
+            let listings = Arc::clone(&self.listings);
+            self.in_flight.push(Box::pin(async move {
+                debug!("walk is going to {}", directory.url);
+                let result =
+                    fetch_entries(fetcher.as_ref(), &scrapers, &parse_options, &listings, directory.url.as_str())
+                        .await
+                        .map(|(entries, _, _)| entries);
+                (directory, result)
+            }));
+        }
+    }
+
+    async fn next(&mut self) -> Option<Result<(String, HttpDirectoryEntry)>> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(item);
+            }
+
+            self.start_pending();
+            let (directory, result) = self.in_flight.next().await?;
+            match result {
+                Ok(entries) => self.process(&directory, entries),
+                Err(e) => self.ready.push_back(Err(e)),
+            }
+        }
+    }
+}
+
+// Returns a stream that walks recursively from `url` whose
+// `entries` have already been retrieved
+pub(crate) fn walk(
+    fetcher: Arc<dyn Fetcher>,
+    scrapers: Arc<ScraperRegistry>,
+    parse_options: Arc<ParseOptions>,
+    listings: Arc<ListingCache>,
+    url: &str,
+    entries: Vec<HttpDirectoryEntry>,
+    options: WalkOptions,
+) -> BoxStream<'static, Result<(String, HttpDirectoryEntry)>> {
+    let walker = Url::parse(url).with_url(url).map(|url| {
+        let mut walker = Walker {
+            fetcher,
+            scrapers,
+            parse_options,
+            listings,
+            options,
+            host: url.host_str().map(str::to_string),
+            visited: HashSet::from([url.clone()]),
+            pending: VecDeque::new(),
+            in_flight: FuturesUnordered::new(),
+            ready: VecDeque::new(),
+        };
+        let root = Directory {
+            url,
+            path: String::new(),
+            depth: 0,
+        };
+        walker.process(&root, entries);
+        walker
+    });
+
+    stream::unfold(Some(walker), |state| async move {
+        match state? {
+            Ok(mut walker) => walker.next().await.map(|item| (item, Some(Ok(walker)))),
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,173 @@
+use crate::{
+    conditional::ListingCache,
+    diff::{ModifiedEntry, diff},
+    error::Result,
+    fetcher::Fetcher,
+    httpdirectory::fetch_entries,
+    httpdirectoryentry::HttpDirectoryEntry,
+    parseoptions::ParseOptions,
+    scrapers::ScraperRegistry,
+};
+use futures::{
+    StreamExt,
+    stream::{self, BoxStream},
+};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Change of a listing returned by `HttpDirectory::watch()`
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// A new entry appeared in the listing
+    Added(HttpDirectoryEntry),
+
+    /// An entry disappeared from the listing
+    Removed(HttpDirectoryEntry),
+
+    /// The date and/or size of an entry changed
+    Modified(Box<ModifiedEntry>),
+}
+
+// State of a watch: the last known listing of `url`. The
+// validators used for conditional requests are the ones of
+// `listings`, shared with the `HttpDirectory` watched
+struct Watcher {
+    fetcher: Arc<dyn Fetcher>,
+    scrapers: Arc<ScraperRegistry>,
+    options: Arc<ParseOptions>,
+    listings: Arc<ListingCache>,
+    url: String,
+    entries: Vec<HttpDirectoryEntry>,
+    interval: Duration,
+    pending: VecDeque<Result<WatchEvent>>,
+}
+
+impl Watcher {
+    // Retrieves the listing once and queues the events (or
+    // the error) found. A 304 answer reuses the cached entries
+    // and does not produce any event
+    async fn poll(&mut self) {
+        match fetch_entries(self.fetcher.as_ref(), &self.scrapers, &self.options, &self.listings, &self.url).await {
+            Ok((entries, _, _)) => {
+                let listing_diff = diff(&self.entries, &entries);
+                if !listing_diff.is_empty() {
+                    debug!("{} changed: {listing_diff:?}", self.url);
+                }
+
+                self.pending.extend(listing_diff.added.into_iter().map(|entry| Ok(WatchEvent::Added(entry))));
+                self.pending.extend(listing_diff.removed.into_iter().map(|entry| Ok(WatchEvent::Removed(entry))));
+                self.pending
+                    .extend(listing_diff.modified.into_iter().map(|entry| Ok(WatchEvent::Modified(Box::new(entry)))));
+                self.entries = entries;
+            }
+            Err(e) => self.pending.push_back(Err(e)),
+        }
+    }
+}
+
+// Polls `url` every `interval` and returns a stream of the changes
+// compared to `entries`, the listing already known
+pub(crate) fn watch(
+    fetcher: Arc<dyn Fetcher>,
+    scrapers: Arc<ScraperRegistry>,
+    options: Arc<ParseOptions>,
+    listings: Arc<ListingCache>,
+    url: &str,
+    entries: Vec<HttpDirectoryEntry>,
+    interval: Duration,
+) -> BoxStream<'static, Result<WatchEvent>> {
+    let watcher = Watcher {
+        fetcher,
+        scrapers,
+        options,
+        listings,
+        url: url.to_string(),
+        entries,
+        interval,
+        pending: VecDeque::new(),
+    };
+
+    stream::unfold(watcher, |mut watcher| async move {
+        loop {
+            if let Some(event) = watcher.pending.pop_front() {
+                return Some((event, watcher));
+            }
+            tokio::time::sleep(watcher.interval).await;
+            watcher.poll().await;
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WatchEvent, watch};
+    use crate::{
+        conditional::ListingCache,
+        fetcher::{FetchResponse, InMemoryFetcher},
+        httpdirectoryentry::HttpDirectoryEntry,
+        parseoptions::ParseOptions,
+        scrapers::ScraperRegistry,
+    };
+    use futures::StreamExt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_watch() {
+        let body = "<ul>\n<li><a href=\"a.iso\">a.iso</a></li>\n<li><a href=\"b.iso\">b.iso</a></li>\n</ul>";
+        let fetcher = InMemoryFetcher::new()
+            .with_response("http://example.org/", FetchResponse::new(200, body).with_header("ETag", "\"v1\""));
+        let known = vec![HttpDirectoryEntry::new("old.iso", "", "", "old.iso")];
+
+        let mut events = watch(
+            Arc::new(fetcher),
+            Arc::new(ScraperRegistry::new()),
+            Arc::new(ParseOptions::default()),
+            Arc::new(ListingCache::default()),
+            "http://example.org/",
+            known,
+            Duration::from_millis(1),
+        );
+
+        let mut names = vec![];
+        for _ in 0..3 {
+            match events.next().await {
+                Some(Ok(WatchEvent::Added(entry))) => names.push(format!("+{}", entry.name().unwrap_or_default())),
+                Some(Ok(WatchEvent::Removed(entry))) => names.push(format!("-{}", entry.name().unwrap_or_default())),
+                other => panic!("Unexpected event {other:?}"),
+            }
+        }
+        assert_eq!(names, vec!["+a.iso", "+b.iso", "-old.iso"]);
+
+        // Next polls are answered with 304 and emit nothing
+        assert!(tokio::time::timeout(Duration::from_millis(50), events.next()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_survives_errors() {
+        let mut events = watch(
+            Arc::new(InMemoryFetcher::new()),
+            Arc::new(ScraperRegistry::new()),
+            Arc::new(ParseOptions::default()),
+            Arc::new(ListingCache::default()),
+            "http://example.org/missing/",
+            vec![],
+            Duration::from_millis(1),
+        );
+
+        for _ in 0..2 {
+            match events.next().await {
+                Some(Err(e)) => {
+                    assert_eq!(
+                        e.to_string(),
+                        "Error retrieving content from 'http://example.org/missing/': 404 Not Found"
+                    );
+                }
+                other => panic!("Unexpected event {other:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+extern crate httpdirectory;
+use httpdirectory::{
+    detect::SiteType,
+    httpdirectory::{HttpDirectory, get_entries_from_body},
+    httpdirectorybuilder::HttpDirectoryBuilder,
+    httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+};
+use httpmock::prelude::*;
+
+const CADDY_BROWSE_INPUT: &str = r#"<!DOCTYPE html>
+<html>
+	<head>
+		<title>/pub/</title>
+		<link rel="canonical" href="/pub/" />
+		<meta charset="utf-8">
+		<meta name="color-scheme" content="light dark">
+		<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<style>
+* { padding: 0; margin: 0; box-sizing: border-box; }
+
+body {
+	font-family: Inter, system-ui, sans-serif;
+	font-size: 16px;
+	text-rendering: optimizespeed;
+	background-color: #f3f6f7;
+	min-height: 100vh;
+}
+
+a {
+	color: #006ed3;
+	text-decoration: none;
+}
+
+.sizebar {
+	position: relative;
+	padding: 0.25rem 0.5rem;
+	display: flex;
+}
+
+.sizebar-bar {
+	background-color: #dbeeff;
+	position: absolute;
+	top: 0;
+	right: 0;
+	bottom: 0;
+	left: 0;
+	height: 100%;
+	pointer-events: none;
+}
+
+.sizebar-text {
+	position: relative;
+	z-index: 1;
+	overflow: hidden;
+	text-overflow: ellipsis;
+	white-space: nowrap;
+}
+</style>
+	</head>
+	<body>
+		<header>
+			<a href="/">
+				<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40" viewBox="0 0 200 200"><path d="M100 0a100 100 0 1 0 0 200a100 100 0 1 0 0-200z"/></svg>
+			</a>
+			<h1>
+				<a href="../">pub</a>/
+			</h1>
+		</header>
+		<main>
+			<div class="meta">
+				<div id="summary">
+					<span class="meta-item">
+						<b>2</b> directories
+					</span>
+					<span class="meta-item">
+						<b>3</b> files
+					</span>
+					<span class="meta-item">
+						<b>1.2 GiB</b> total
+					</span>
+				</div>
+			</div>
+			<div class='listing'>
+			<table aria-describedby="summary">
+				<thead>
+				<tr>
+					<th></th>
+					<th>
+						<a href="?sort=namedirfirst&order=desc" class="icon">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-caret-up" width="24" height="24" viewBox="0 0 24 24"><path d="M18 14l-6 -6l-6 6h12"></path></svg>
+						</a>
+						<a href="?sort=name&order=asc">
+							Name
+						</a>
+					</th>
+					<th class="size">
+						<a href="?sort=size&order=asc">
+							Size
+						</a>
+					</th>
+					<th class="timestamp hideable">
+						<a href="?sort=time&order=asc">
+							Modified
+						</a>
+					</th>
+					<th class="hideable"></th>
+				</tr>
+				</thead>
+				<tbody>
+				<tr>
+					<td></td>
+					<td>
+						<a href="..">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-corner-left-up" width="24" height="24" viewBox="0 0 24 24"><path d="M18 18h-6a3 3 0 0 1 -3 -3v-10l-4 4m8 0l-4 -4"></path></svg>
+							<span class="go-up">Up</span>
+						</a>
+					</td>
+					<td>&mdash;</td>
+					<td class="timestamp hideable">&mdash;</td>
+					<td class="hideable"></td>
+				</tr>
+				<tr class="file">
+					<td></td>
+					<td>
+						<a href="./debian/">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-folder-filled" width="24" height="24" viewBox="0 0 24 24"><path d="M9 3a1 1 0 0 1 .608 .206l.1 .087l2.706 2.707h6.586a3 3 0 0 1 2.995 2.824l.005 .176v8a3 3 0 0 1 -2.824 2.995l-.176 .005h-14a3 3 0 0 1 -2.995 -2.824l-.005 -.176v-11a3 3 0 0 1 2.824 -2.995l.176 -.005h4z"></path></svg>
+							<span class="name">debian/</span>
+						</a>
+					</td>
+					<td data-order="-1">&mdash;</td>
+					<td class="timestamp hideable">
+						<time datetime="2025-05-31T13:54:45Z">05/31/2025 01:54:45 PM +00:00</time>
+					</td>
+					<td class="hideable"></td>
+				</tr>
+				<tr class="file">
+					<td></td>
+					<td>
+						<a href="./ubuntu/">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-folder-filled" width="24" height="24" viewBox="0 0 24 24"><path d="M9 3a1 1 0 0 1 .608 .206l.1 .087l2.706 2.707h6.586a3 3 0 0 1 2.995 2.824l.005 .176v8a3 3 0 0 1 -2.824 2.995l-.176 .005h-14a3 3 0 0 1 -2.995 -2.824l-.005 -.176v-11a3 3 0 0 1 2.824 -2.995l.176 -.005h4z"></path></svg>
+							<span class="name">ubuntu/</span>
+						</a>
+					</td>
+					<td data-order="-1">&mdash;</td>
+					<td class="timestamp hideable">
+						<time datetime="2025-04-30T16:31:02Z">04/30/2025 04:31:02 PM +02:00</time>
+					</td>
+					<td class="hideable"></td>
+				</tr>
+				<tr class="file">
+					<td></td>
+					<td>
+						<a href="./SHA256SUMS">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-file" width="24" height="24" viewBox="0 0 24 24"><path d="M14 3v4a1 1 0 0 0 1 1h4"></path></svg>
+							<span class="name">SHA256SUMS</span>
+						</a>
+					</td>
+					<td data-order="1329">
+						<div class="sizebar">
+							<div class="sizebar-bar"></div>
+							<div class="sizebar-text">
+								1.3 KiB
+							</div>
+						</div>
+					</td>
+					<td class="timestamp hideable">
+						<time datetime="2025-05-13T03:57:12Z">05/13/2025 03:57:12 AM +00:00</time>
+					</td>
+					<td class="hideable"></td>
+				</tr>
+				<tr class="file">
+					<td></td>
+					<td>
+						<a href="./debian-12-generic-amd64.qcow2">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-file-zip" width="24" height="24" viewBox="0 0 24 24"><path d="M6 20.735a2 2 0 0 1 -1 -1.735v-14a2 2 0 0 1 2 -2h7l5 5v11a2 2 0 0 1 -2 2h-1"></path></svg>
+							<span class="name">debian-12-generic-amd64.qcow2</span>
+						</a>
+					</td>
+					<td data-order="1289224192">
+						<div class="sizebar">
+							<div class="sizebar-bar"></div>
+							<div class="sizebar-text">
+								1.2 GiB
+							</div>
+						</div>
+					</td>
+					<td class="timestamp hideable">
+						<time datetime="2025-05-12T23:57:00Z">05/12/2025 11:57:00 PM +00:00</time>
+					</td>
+					<td class="hideable"></td>
+				</tr>
+				<tr class="file">
+					<td></td>
+					<td>
+						<a href="./release%20notes.txt">
+							<svg xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-file-text" width="24" height="24" viewBox="0 0 24 24"><path d="M14 3v4a1 1 0 0 0 1 1h4"></path></svg>
+							<span class="name">release notes.txt</span>
+						</a>
+					</td>
+					<td data-order="11">
+						<div class="sizebar">
+							<div class="sizebar-bar"></div>
+							<div class="sizebar-text">
+								11 B
+							</div>
+						</div>
+					</td>
+					<td class="timestamp hideable">
+						<time datetime="2025-05-13T04:00:00Z">05/13/2025 04:00:00 AM +00:00</time>
+					</td>
+					<td class="hideable"></td>
+				</tr>
+				</tbody>
+			</table>
+			</div>
+		</main>
+		<footer>
+			Served with
+			<a rel="noopener noreferrer" href="https://caddyserver.com">Caddy</a>
+		</footer>
+	</body>
+</html>
+"#;
+
+const CADDY_JSON_INPUT: &str = r#"[{"name":"debian/","size":4096,"url":"./debian/","mod_time":"2025-05-31T13:54:45.318532874Z","mode":2147484141,"is_dir":true,"is_symlink":false},{"name":"ubuntu/","size":4096,"url":"./ubuntu/","mod_time":"2025-04-30T16:31:02.12+02:00","mode":2147484141,"is_dir":true,"is_symlink":false},{"name":"SHA256SUMS","size":1329,"url":"./SHA256SUMS","mod_time":"2025-05-13T03:57:12.5Z","mode":420,"is_dir":false,"is_symlink":false},{"name":"debian-12-generic-amd64.qcow2","size":1289224192,"url":"./debian-12-generic-amd64.qcow2","mod_time":"2025-05-12T23:57:00Z","mode":420,"is_dir":false,"is_symlink":false},{"name":"release notes.txt","size":11,"url":"./release%20notes.txt","mod_time":"2025-05-13T04:00:00Z","mode":420,"is_dir":false,"is_symlink":false}]"#;
+
+// Both listings have the same files and directories. The HTML
+// one has an additional parent directory entry first
+fn assert_caddy_entries(entries: &[HttpDirectoryEntry]) {
+    assert_eq!(entries.len(), 5);
+    assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "2025-05-31 13:54");
+    assert_entry(&entries[1], &EntryType::Directory, "ubuntu/", 0, "2025-04-30 16:31");
+    assert_entry(&entries[2], &EntryType::File, "SHA256SUMS", 1329, "2025-05-13 03:57");
+    assert_entry(&entries[3], &EntryType::File, "debian-12-generic-amd64.qcow2", 1_289_224_192, "2025-05-12 23:57");
+    assert_entry(&entries[4], &EntryType::File, "release notes.txt", 11, "2025-05-13 04:00");
+
+    // Sizes are exact and dates keep their offset
+    match &entries[3] {
+        HttpDirectoryEntry::File(entry) => assert_eq!(entry.exact_size(), Some(1_289_224_192)),
+        other => panic!("Unexpected entry {other:?}"),
+    }
+    assert_eq!(entries[1].date().map(|date| date.offset().to_string()), Some("+02:00".to_string()));
+}
+
+fn assert_caddy_browse_entries(entries: &[HttpDirectoryEntry]) {
+    assert_eq!(entries.len(), 6);
+    assert_entry(&entries[0], &EntryType::ParentDirectory, "..", 0, "0000-00-00 00:00");
+    assert_caddy_entries(&entries[1..]);
+}
+
+#[allow(dead_code)]
+pub async fn mock_caddy_browse() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start();
+    let url = server.url("/pub/");
+
+    let html_mock = server.mock(|when, then| {
+        when.path("/pub/").header_not("accept", "application/json");
+        then.status(200).header("Content-Type", "text/html; charset=utf-8").body(CADDY_BROWSE_INPUT);
+    });
+    let json_mock = server.mock(|when, then| {
+        when.path("/pub/").header("accept", "application/json");
+        then.status(200).header("Content-Type", "application/json").body(CADDY_JSON_INPUT);
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("caddy", None)));
+    assert_caddy_browse_entries(httpdir.entries());
+
+    let httpdir = HttpDirectoryBuilder::new().header("Accept", "application/json").build(&url).await?;
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("caddy-json", None)));
+    assert_caddy_entries(httpdir.entries());
+    assert_eq!(
+        httpdir.files().last().and_then(HttpDirectoryEntry::url).map(ToString::to_string),
+        Some(server.url("/pub/release%20notes.txt"))
+    );
+
+    html_mock.assert();
+    json_mock.assert();
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn run_caddy_browse() -> Result<(), Box<dyn std::error::Error>> {
+    assert_caddy_browse_entries(&get_entries_from_body(CADDY_BROWSE_INPUT));
+
+    // The JSON listing is recognized by its shape without any content type
+    let httpdir = HttpDirectory::from_html("https://example.org/pub/", CADDY_JSON_INPUT)?;
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("caddy-json", None)));
+    assert_caddy_entries(httpdir.entries());
+    Ok(())
+}
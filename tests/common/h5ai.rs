@@ -1,5 +1,6 @@
 extern crate httpdirectory;
 use httpdirectory::{
+    detect::SiteType,
     httpdirectory::{HttpDirectory, get_entries_from_body},
     httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
 };
@@ -167,6 +168,12 @@ pub async fn mock_debian_h5ai() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => panic!("{e}"),
     };
 
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("h5ai", Some("v0.29.2"))));
+
+    // Root relative links are resolved against the url of the listing
+    let dists = httpdir.dirs().first().and_then(|entry| entry.url().map(ToString::to_string));
+    assert_eq!(dists, Some(server.url("/debian/dists/")));
+
     let entries = httpdir.entries();
     assert_debian_h5ai_entries(entries);
 
@@ -0,0 +1,62 @@
+extern crate httpdirectory;
+use httpdirectory::{
+    detect::SiteType,
+    httpdirectory::{HttpDirectory, get_entries_from_body},
+    httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+};
+use httpmock::prelude::*;
+
+const IIS_INPUT: &str = r#"<html><head><title>mirror.example.org - /pub/</title></head><body><H1>mirror.example.org - /pub/</H1><hr>
+
+<pre><A HREF="/">[To Parent Directory]</A><br><br> 5/31/2025  1:54 PM        &lt;dir&gt; <A HREF="/pub/debian/">debian</A><br>12/17/2024 10:05 AM        &lt;dir&gt; <A HREF="/pub/windows%20updates/">windows updates</A><br> 1/6/2025 12:30 AM           0 <A HREF="/pub/empty.txt">empty.txt</A><br> 5/31/2025  1:54 PM        12345 <A HREF="/pub/README.txt">README.txt</A><br>10/21/2025 11:59 PM   4712300544 <A HREF="/pub/server.iso">server.iso</A><br></pre><hr></body></html>
+"#;
+
+fn assert_iis_entries(entries: &[HttpDirectoryEntry]) {
+    assert_eq!(entries.len(), 6);
+    assert_entry(&entries[0], &EntryType::ParentDirectory, "/", 0, "0000-00-00 00:00");
+    assert_entry(&entries[1], &EntryType::Directory, "debian/", 0, "2025-05-31 13:54");
+    assert_entry(&entries[2], &EntryType::Directory, "windows updates/", 0, "2024-12-17 10:05");
+    assert_entry(&entries[3], &EntryType::File, "empty.txt", 0, "2025-01-06 00:30");
+    assert_entry(&entries[4], &EntryType::File, "README.txt", 12_345, "2025-05-31 13:54");
+    assert_entry(&entries[5], &EntryType::File, "server.iso", 4_712_300_544, "2025-10-21 23:59");
+
+    match &entries[5] {
+        HttpDirectoryEntry::File(entry) => {
+            assert_eq!(entry.link(), "/pub/server.iso");
+            assert_eq!(entry.exact_size(), Some(4_712_300_544));
+        }
+        other => panic!("Unexpected entry {other:?}"),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn mock_iis() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start();
+    let url = server.url("/pub/");
+    let mock = server.mock(|when, then| {
+        when.path("/pub/");
+        then.status(200).header("Server", "Microsoft-IIS/10.0").body(IIS_INPUT);
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("iis", None)));
+    assert_iis_entries(httpdir.entries());
+    assert_eq!(httpdir.dirs().len(), 2);
+    assert_eq!(httpdir.files().len(), 3);
+
+    mock.assert();
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn run_iis() -> Result<(), Box<dyn std::error::Error>> {
+    assert_iis_entries(&get_entries_from_body(IIS_INPUT));
+
+    let httpdir = HttpDirectory::from_html("https://mirror.example.org/pub/", IIS_INPUT)?;
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("iis", None)));
+    assert_eq!(httpdir.dirs().len(), 2);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+extern crate httpdirectory;
+use httpdirectory::{
+    detect::SiteType,
+    httpdirectory::{HttpDirectory, get_entries_from_body},
+    httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+};
+use httpmock::prelude::*;
+
+const LIGHTTPD_INPUT: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="UTF-8">
+<title>Index of /pub/</title>
+<style type="text/css">
+a, a:active {text-decoration: none; color: blue;}
+a:visited {color: #48468F;}
+a:hover, a:focus {text-decoration: underline; color: red;}
+body {background-color: #F5F5F5;}
+h2 {margin-bottom: 12px;}
+table {margin-left: 12px;}
+th, td { font: 90% monospace; text-align: left;}
+th { font-weight: bold; padding-right: 14px; padding-bottom: 3px;}
+td {padding-right: 14px;}
+td.s, th.s {text-align: right;}
+div.list { background-color: white; border-top: 1px solid #646464; border-bottom: 1px solid #646464; padding-top: 10px; padding-bottom: 14px;}
+div.foot { font: 90% monospace; color: #787878; padding-top: 4px;}
+</style>
+</head>
+<body>
+<h2>Index of /pub/</h2>
+<div class="list">
+<table summary="Directory Listing" cellpadding="0" cellspacing="0">
+<thead><tr><th class="n">Name</th><th class="m">Last Modified</th><th class="s">Size</th><th class="t">Type</th></tr></thead>
+<tbody>
+<tr class="d"><td class="n"><a href="../">..</a>/</td><td class="m">&nbsp;</td><td class="s">- &nbsp;</td><td class="t">Directory</td></tr>
+<tr class="d"><td class="n"><a href="firmware/">firmware</a>/</td><td class="m">2024-Apr-05 11:59:30</td><td class="s">- &nbsp;</td><td class="t">Directory</td></tr>
+<tr class="d"><td class="n"><a href="packages/">packages</a>/</td><td class="m">2025-May-13 03:57:02</td><td class="s">- &nbsp;</td><td class="t">Directory</td></tr>
+<tr><td class="n"><a href="README.txt">README.txt</a></td><td class="m">2025-May-12 17:21:45</td><td class="s">4.2K</td><td class="t">text/plain;charset=utf-8</td></tr>
+<tr><td class="n"><a href="openwrt-23.05.5-ath79-generic-sysupgrade.bin">openwrt-23.05.5-ath79-generic-sysupgrade.bin</a></td><td class="m">2024-Sep-23 12:34:06</td><td class="s">6.5M</td><td class="t">application/octet-stream</td></tr>
+<tr><td class="n"><a href="rootfs.tar.gz">rootfs.tar.gz</a></td><td class="m">2025-Jan-07 19:40:11</td><td class="s">1.1G</td><td class="t">application/x-gtar-compressed</td></tr>
+<tr><td class="n"><a href="sha256sums">sha256sums</a></td><td class="m">2025-May-13 04:00:59</td><td class="s">723</td><td class="t">application/octet-stream</td></tr>
+</tbody>
+</table>
+</div>
+<div class="foot">lighttpd/1.4.76</div>
+</body>
+</html>
+"#;
+
+fn assert_lighttpd_entries(entries: &[HttpDirectoryEntry]) {
+    assert_eq!(entries.len(), 7);
+    assert_entry(&entries[0], &EntryType::ParentDirectory, "../", 0, "0000-00-00 00:00");
+    assert_entry(&entries[1], &EntryType::Directory, "firmware/", 0, "2024-04-05 11:59");
+    assert_entry(&entries[2], &EntryType::Directory, "packages/", 0, "2025-05-13 03:57");
+    assert_entry(&entries[3], &EntryType::File, "README.txt", 4_300, "2025-05-12 17:21");
+    assert_entry(
+        &entries[4],
+        &EntryType::File,
+        "openwrt-23.05.5-ath79-generic-sysupgrade.bin",
+        6_815_744,
+        "2024-09-23 12:34",
+    );
+    assert_entry(&entries[5], &EntryType::File, "rootfs.tar.gz", 1_181_116_006, "2025-01-07 19:40");
+    assert_entry(&entries[6], &EntryType::File, "sha256sums", 723, "2025-05-13 04:00");
+
+    // The Type column is kept
+    assert_eq!(entries[1].extra_column("Type"), Some("Directory"));
+    assert_eq!(entries[3].extra_column("type"), Some("text/plain;charset=utf-8"));
+    assert_eq!(entries[6].extra_column("Type"), Some("application/octet-stream"));
+}
+
+#[allow(dead_code)]
+pub async fn mock_lighttpd() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start();
+    let url = server.url("/pub/");
+    let mock = server.mock(|when, then| {
+        when.path("/pub/");
+        then.status(200).header("Server", "lighttpd/1.4.76").body(LIGHTTPD_INPUT);
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("lighttpd", Some("1.4.76"))));
+    assert_lighttpd_entries(httpdir.entries());
+    assert_eq!(httpdir.dirs().len(), 2);
+    assert_eq!(httpdir.files().len(), 4);
+
+    mock.assert();
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn run_lighttpd() -> Result<(), Box<dyn std::error::Error>> {
+    assert_lighttpd_entries(&get_entries_from_body(LIGHTTPD_INPUT));
+
+    // Without the footer (server.tag set to something else) the version is unknown
+    let body = LIGHTTPD_INPUT.replace("lighttpd/1.4.76", "mirror");
+    let httpdir = HttpDirectory::from_html("https://example.org/pub/", &body)?;
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("lighttpd", None)));
+    assert_lighttpd_entries(httpdir.entries());
+    Ok(())
+}
@@ -1,6 +1,10 @@
+pub mod caddy;
 pub mod h5ai;
+pub mod iis;
+pub mod lighttpd;
 pub mod miniserve;
 pub mod pre;
+pub mod s3;
 pub mod snt;
 pub mod stil;
 pub mod table;
@@ -1,5 +1,6 @@
 extern crate httpdirectory;
 use httpdirectory::{
+    detect::SiteType,
     httpdirectory::{HttpDirectory, get_entries_from_body},
     httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
 };
@@ -75,6 +76,8 @@ pub async fn mock_bsd_example() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => panic!("{e}"),
     };
 
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("pre", None)));
+
     let entries = httpdir.entries();
     assert_bsd_example_entries(entries);
 
@@ -246,6 +249,8 @@ fn assert_pre_img_example_entries(entries: &Vec<HttpDirectoryEntry>) {
     assert_entry(&entries[2], &EntryType::File, "MD5SUMS.gpg", 833, "2025-05-01 16:23");
     assert_entry(&entries[3], &EntryType::File, "SHA256SUMS", 7_168, "2025-05-01 16:23");
     assert_entry(&entries[4], &EntryType::File, "SHA256SUMS.gpg", 833, "2025-05-01 16:23");
+    assert_eq!(entries[4].description(), None);
+    assert_eq!(entries[6].description(), Some("File system image and Kernel packed"));
 
     assert_entry(&entries[69], &EntryType::Directory, "unpacked/", 0, "2025-05-01 16:23");
 }
@@ -0,0 +1,125 @@
+extern crate httpdirectory;
+use httpdirectory::{
+    detect::SiteType,
+    httpdirectory::{HttpDirectory, get_entries_from_body},
+    httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
+};
+use httpmock::prelude::*;
+
+// First page of the root of the bucket (ListObjectsV2 with `/` as delimiter)
+const S3_ROOT_PAGE_1_INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><Name>mirror</Name><Prefix></Prefix><NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken><KeyCount>2</KeyCount><MaxKeys>2</MaxKeys><Delimiter>/</Delimiter><IsTruncated>true</IsTruncated><Contents><Key>README.txt</Key><LastModified>2025-05-31T13:54:45.000Z</LastModified><ETag>&#34;a8c5e6b0d0c4b2cfe1d8e2e2a7a0d4f1&#34;</ETag><Size>12345</Size><Owner><ID>02d6176db174dc93cb1b899f7c6078f08654445fe8cf1b6ce98d8855f66bdbf4</ID><DisplayName>minio</DisplayName></Owner><StorageClass>STANDARD</StorageClass></Contents><CommonPrefixes><Prefix>debian/</Prefix></CommonPrefixes></ListBucketResult>"#;
+
+// Second (and last) page of the root of the bucket
+const S3_ROOT_PAGE_2_INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><Name>mirror</Name><Prefix></Prefix><ContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</ContinuationToken><KeyCount>2</KeyCount><MaxKeys>2</MaxKeys><Delimiter>/</Delimiter><IsTruncated>false</IsTruncated><Contents><Key>SHA256SUMS</Key><LastModified>2025-06-01T08:30:00.000Z</LastModified><ETag>&#34;0f343b0931126a20f133d67c2b018a3b&#34;</ETag><Size>1024</Size><StorageClass>STANDARD</StorageClass></Contents><Contents><Key>ubuntu-24.04-live-server-amd64.iso</Key><LastModified>2024-04-23T19:58:16.000Z</LastModified><ETag>&#34;8fdb6f2ad8c7dd63ee36d4a3a0ab37a8-302&#34;</ETag><Size>2754981888</Size><StorageClass>STANDARD</StorageClass></Contents><CommonPrefixes><Prefix>ubuntu/</Prefix></CommonPrefixes></ListBucketResult>"#;
+
+// Listing of the `debian/` prefix
+const S3_DEBIAN_INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><Name>mirror</Name><Prefix>debian/</Prefix><KeyCount>3</KeyCount><MaxKeys>1000</MaxKeys><Delimiter>/</Delimiter><IsTruncated>false</IsTruncated><Contents><Key>debian/</Key><LastModified>2025-05-30T08:00:00.000Z</LastModified><ETag>&#34;d41d8cd98f00b204e9800998ecf8427e&#34;</ETag><Size>0</Size><StorageClass>STANDARD</StorageClass></Contents><Contents><Key>debian/Release notes.txt</Key><LastModified>2025-05-30T08:00:00.000Z</LastModified><ETag>&#34;b9d6f4a2c3e1d0f9a8b7c6d5e4f3a2b1&#34;</ETag><Size>42</Size><StorageClass>STANDARD</StorageClass></Contents><CommonPrefixes><Prefix>debian/dists/</Prefix></CommonPrefixes></ListBucketResult>"#;
+
+fn assert_s3_root_entries(entries: &[HttpDirectoryEntry]) {
+    assert_eq!(entries.len(), 5);
+    assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "0000-00-00 00:00");
+    assert_entry(&entries[1], &EntryType::File, "README.txt", 12_345, "2025-05-31 13:54");
+    assert_entry(&entries[2], &EntryType::Directory, "ubuntu/", 0, "0000-00-00 00:00");
+    assert_entry(&entries[3], &EntryType::File, "SHA256SUMS", 1024, "2025-06-01 08:30");
+    assert_entry(
+        &entries[4],
+        &EntryType::File,
+        "ubuntu-24.04-live-server-amd64.iso",
+        2_754_981_888,
+        "2024-04-23 19:58",
+    );
+
+    match &entries[4] {
+        HttpDirectoryEntry::File(entry) => {
+            assert_eq!(entry.exact_size(), Some(2_754_981_888));
+            assert!(entry.date_has_offset());
+        }
+        other => panic!("Unexpected entry {other:?}"),
+    }
+}
+
+#[allow(dead_code)]
+pub async fn mock_s3_bucket() -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer::start();
+    let page_1 = server.mock(|when, then| {
+        when.path("/mirror/").query_param_missing("prefix").query_param_missing("continuation-token");
+        then.status(200).header("Content-Type", "application/xml").body(S3_ROOT_PAGE_1_INPUT);
+    });
+    let page_2 = server.mock(|when, then| {
+        when.path("/mirror/")
+            .query_param("list-type", "2")
+            .query_param("continuation-token", "1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=");
+        then.status(200).header("Content-Type", "application/xml").body(S3_ROOT_PAGE_2_INPUT);
+    });
+    let debian = server.mock(|when, then| {
+        when.path("/mirror/")
+            .query_param("list-type", "2")
+            .query_param("prefix", "debian/")
+            .query_param("delimiter", "/");
+        then.status(200).header("Content-Type", "application/xml").body(S3_DEBIAN_INPUT);
+    });
+
+    let url = server.url("/mirror/?list-type=2&delimiter=/");
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("s3", None)));
+    assert_s3_root_entries(httpdir.entries());
+    assert_eq!(
+        httpdir.entries()[4].url().map(ToString::to_string),
+        Some(server.url("/mirror/ubuntu-24.04-live-server-amd64.iso"))
+    );
+    page_1.assert();
+    page_2.assert();
+
+    // cd() lists the prefix of the directory
+    let httpdir = httpdir.cd("debian/").await?;
+    assert_eq!(httpdir.len(), 2);
+    assert_entry(&httpdir.entries()[0], &EntryType::Directory, "dists/", 0, "0000-00-00 00:00");
+    assert_entry(&httpdir.entries()[1], &EntryType::File, "Release notes.txt", 42, "2025-05-30 08:00");
+    assert_eq!(
+        httpdir.entries()[1].url().map(ToString::to_string),
+        Some(server.url("/mirror/debian/Release%20notes.txt"))
+    );
+    assert_eq!(
+        httpdir.entries()[0].url().map(ToString::to_string),
+        Some(server.url("/mirror/?list-type=2&prefix=debian%2Fdists%2F&delimiter=%2F"))
+    );
+    debian.assert();
+
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn run_s3_bucket() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = get_entries_from_body(S3_ROOT_PAGE_1_INPUT);
+    assert_eq!(entries.len(), 2);
+    assert_entry(&entries[0], &EntryType::Directory, "debian/", 0, "0000-00-00 00:00");
+    assert_entry(&entries[1], &EntryType::File, "README.txt", 12_345, "2025-05-31 13:54");
+
+    let mut entries = get_entries_from_body(S3_ROOT_PAGE_1_INPUT);
+    entries.extend(get_entries_from_body(S3_ROOT_PAGE_2_INPUT));
+    assert_s3_root_entries(&entries);
+
+    // Without network access the next pages are not retrieved
+    let httpdir = HttpDirectory::from_html("https://mirror.s3.amazonaws.com/", S3_DEBIAN_INPUT)?;
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("s3", None)));
+    assert_eq!(httpdir.dirs().len(), 1);
+    assert_eq!(httpdir.files().len(), 1);
+
+    // Links of a path style bucket given without its trailing `/` keep the bucket name
+    let httpdir = HttpDirectory::from_html("http://localhost:9000/mirror?list-type=2", S3_ROOT_PAGE_1_INPUT)?;
+    assert_eq!(
+        httpdir.entries()[1].url().map(ToString::to_string),
+        Some("http://localhost:9000/mirror/README.txt".to_string())
+    );
+    assert_eq!(
+        httpdir.entries()[0].url().map(ToString::to_string),
+        Some("http://localhost:9000/mirror/?list-type=2&prefix=debian%2F&delimiter=%2F".to_string())
+    );
+    Ok(())
+}
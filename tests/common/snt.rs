@@ -1,5 +1,6 @@
 extern crate httpdirectory;
 use httpdirectory::{
+    detect::SiteType,
     httpdirectory::{HttpDirectory, get_entries_from_body},
     httpdirectoryentry::{EntryType, HttpDirectoryEntry, assert_entry},
 };
@@ -265,6 +266,8 @@ pub async fn mock_debian_snt() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => panic!("{e}"),
     };
 
+    assert_eq!(httpdir.site_type(), Some(&SiteType::new("snt", None)));
+
     let entries = httpdir.entries();
     assert_debian_snt_entries(entries);
 
@@ -1,6 +1,19 @@
 extern crate httpdirectory;
-use httpdirectory::httpdirectory::HttpDirectory;
+use futures::StreamExt;
+use httpdirectory::{
+    cache::DiskCache,
+    detect::SiteType,
+    fetcher::InMemoryFetcher,
+    httpdirectory::HttpDirectory,
+    httpdirectorybuilder::HttpDirectoryBuilder,
+    httpdirectoryentry::HttpDirectoryEntry,
+    retry::RetryPolicy,
+    scrapers::{Confidence, Scraper},
+    walk::WalkOptions,
+    watch::WatchEvent,
+};
 use httpmock::prelude::*;
+use std::time::Duration;
 use unwrap_unreachable::UnwrapUnreachable;
 mod common;
 
@@ -43,6 +56,569 @@ async fn test_empty_404_status() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn test_builder_user_agent_kept_by_cd() {
+    let server = MockServer::start();
+    let url = server.url("/hello/");
+
+    let root_mock = server.mock(|when, then| {
+        when.path("/hello/").header("user-agent", "mirror-checker/1.0").header("x-token", "secret");
+        then.status(200);
+    });
+    let dir_mock = server.mock(|when, then| {
+        when.path("/hello/world/").header("user-agent", "mirror-checker/1.0").header("x-token", "secret");
+        then.status(200);
+    });
+
+    let httpdir = match HttpDirectoryBuilder::new()
+        .user_agent("mirror-checker/1.0")
+        .header("X-Token", "secret")
+        .build(&url)
+        .await
+    {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+
+    match httpdir.cd("world/").await {
+        Ok(httpdir) => assert!(httpdir.is_empty()),
+        Err(e) => panic!("{e}"),
+    }
+
+    root_mock.assert();
+    dir_mock.assert();
+}
+
+#[tokio::test]
+async fn test_builder_max_body_size() {
+    let server = MockServer::start();
+    let url = server.url("/hello");
+
+    let mock = server.mock(|when, then| {
+        when.path("/hello");
+        then.status(200).body("<html><body><ul><li><a href=\"file\">file</a></li></ul></body></html>");
+    });
+
+    match HttpDirectoryBuilder::new().max_body_size(16).build(&url).await {
+        Ok(httpdir) => panic!("This test should return an Error. We got {httpdir:?}"),
+        Err(e) => assert_eq!(e.to_string(), format!("Content from '{url}' is bigger than 16 bytes")),
+    }
+
+    match HttpDirectoryBuilder::new().max_body_size(1_024).build(&url).await {
+        Ok(httpdir) => assert_eq!(httpdir.len(), 1),
+        Err(e) => panic!("{e}"),
+    }
+
+    mock.assert_calls(2);
+}
+
+// Returns a python's http.server like body listing `links`
+fn ul_body(links: &[&str]) -> String {
+    let items: Vec<String> = links.iter().map(|link| format!("<li><a href=\"{link}\">{link}</a></li>")).collect();
+    format!("<html><body><ul>\n{}\n</ul></body></html>", items.join("\n"))
+}
+
+#[tokio::test]
+async fn test_walk() {
+    let server = MockServer::start();
+    let url = server.url("/root/");
+
+    let root = server.mock(|when, then| {
+        when.path("/root/");
+        then.status(200).body(ul_body(&["../", "a/", "broken/", "file1"]));
+    });
+    let a = server.mock(|when, then| {
+        when.path("/root/a/");
+        then.status(200).body(ul_body(&["../", "b/", "file2"]));
+    });
+    let b = server.mock(|when, then| {
+        when.path("/root/a/b/");
+        then.status(200).body(ul_body(&["/root/", "file3"]));
+    });
+    let broken = server.mock(|when, then| {
+        when.path("/root/broken/");
+        then.status(404);
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+
+    let items: Vec<_> = httpdir.walk(WalkOptions::new().concurrency(1)).collect().await;
+    let mut paths: Vec<String> =
+        items.iter().filter_map(|item| item.as_ref().ok()).map(|(path, _)| path.clone()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["a/", "a/b/", "a/b/file3", "a/b/root/", "a/file2", "broken/", "file1"]);
+
+    let errors: Vec<_> = items.iter().filter_map(|item| item.as_ref().err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), format!("Error retrieving content from '{url}broken/': 404 Not Found"));
+
+    // root directory is not retrieved again (cycle)
+    root.assert_calls(1);
+    a.assert();
+    b.assert();
+    broken.assert();
+}
+
+#[tokio::test]
+async fn test_walk_max_depth() {
+    let server = MockServer::start();
+    let url = server.url("/root/");
+
+    let root = server.mock(|when, then| {
+        when.path("/root/");
+        then.status(200).body(ul_body(&["a/", "file1"]));
+    });
+    let a = server.mock(|when, then| {
+        when.path("/root/a/");
+        then.status(200).body(ul_body(&["b/", "file2"]));
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+
+    let items: Vec<_> = httpdir.walk(WalkOptions::new().max_depth(0)).collect().await;
+    assert_eq!(items.len(), 2);
+
+    let items: Vec<_> = httpdir.walk(WalkOptions::new().max_depth(1)).collect().await;
+    let mut paths: Vec<String> = items.into_iter().filter_map(Result::ok).map(|(path, _)| path).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["a/", "a/b/", "a/file2", "file1"]);
+
+    root.assert();
+    a.assert();
+}
+
+#[tokio::test]
+async fn test_walk_same_host() {
+    let server = MockServer::start();
+    let url = server.url("/root/");
+
+    let root = server.mock(|when, then| {
+        when.path("/root/");
+        then.status(200).body(ul_body(&["https://elsewhere.invalid/dir/", "file1"]));
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+
+    let items: Vec<_> = httpdir.walk(WalkOptions::new()).collect().await;
+    assert!(items.iter().all(Result::is_ok));
+    assert_eq!(items.len(), 2);
+
+    root.assert();
+}
+
+#[tokio::test]
+async fn test_retry_policy_transient_status() {
+    let server = MockServer::start();
+    let url = server.url("/busy");
+
+    let mock = server.mock(|when, then| {
+        when.path("/busy");
+        then.status(503).header("Retry-After", "0");
+    });
+
+    let policy = RetryPolicy::new().max_attempts(3).initial_backoff(Duration::from_millis(10));
+    match HttpDirectoryBuilder::new().retry_policy(policy).build(&url).await {
+        Ok(_) => panic!("This test should return Err()"),
+        Err(e) => assert_eq!(e.to_string(), format!("Error retrieving content from '{url}': 503 Service Unavailable")),
+    }
+
+    mock.assert_calls(3);
+}
+
+#[tokio::test]
+async fn test_retry_policy_not_retryable_status() {
+    let server = MockServer::start();
+    let url = server.url("/missing");
+
+    let mock = server.mock(|when, then| {
+        when.path("/missing");
+        then.status(404);
+    });
+
+    let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(10));
+    assert!(HttpDirectoryBuilder::new().retry_policy(policy).build(&url).await.is_err());
+
+    mock.assert_calls(1);
+}
+
+#[tokio::test]
+async fn test_in_memory_fetcher() {
+    let fetcher = InMemoryFetcher::new()
+        .with_body("http://mirror.example/", &ul_body(&["pub/", "README"]))
+        .with_body("http://mirror.example/pub/", &ul_body(&["file.iso"]));
+
+    let httpdir = match HttpDirectory::with_fetcher("http://mirror.example/", fetcher.clone()).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.len(), 2);
+
+    let paths: Vec<String> =
+        httpdir.walk(WalkOptions::new()).filter_map(|item| async { item.ok() }).map(|(path, _)| path).collect().await;
+    assert_eq!(paths, vec!["pub/", "README", "pub/file.iso"]);
+
+    // A concurrency set to 0 without the setter is read as 1
+    let options = WalkOptions {
+        concurrency: 0,
+        ..WalkOptions::default()
+    };
+    let paths: Vec<String> =
+        httpdir.walk(options).filter_map(|item| async { item.ok() }).map(|(path, _)| path).collect().await;
+    assert_eq!(paths, vec!["pub/", "README", "pub/file.iso"]);
+
+    match httpdir.cd("pub/").await {
+        Ok(httpdir) => assert_eq!(httpdir.files().first().and_then(HttpDirectoryEntry::name), Some("file.iso")),
+        Err(e) => panic!("{e}"),
+    }
+
+    match HttpDirectoryBuilder::new().fetcher(fetcher).build("http://mirror.example/missing/").await {
+        Ok(_) => panic!("This test should return Err()"),
+        Err(e) => {
+            assert_eq!(e.to_string(), "Error retrieving content from 'http://mirror.example/missing/': 404 Not Found");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_load_checksums_and_verify() {
+    let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    let fetcher = InMemoryFetcher::new()
+        .with_body("http://mirror.example/", &ul_body(&["MD5SUMS", "SHA256SUMS", "hello.txt", "other.txt"]))
+        .with_body("http://mirror.example/MD5SUMS", "5d41402abc4b2a76b9719d911017c592  hello.txt\n")
+        .with_body("http://mirror.example/SHA256SUMS", &format!("{sha256}  hello.txt\n{}  other.txt\n", "0".repeat(64)))
+        .with_body("http://mirror.example/hello.txt", "hello")
+        .with_body("http://mirror.example/other.txt", "other");
+
+    let httpdir = match HttpDirectory::with_fetcher("http://mirror.example/", fetcher).await {
+        Ok(httpdir) => httpdir.load_checksums().await.unreachable(),
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.checksum_manifests().len(), 2);
+
+    let files = httpdir.files().filter_by_name("txt$").unreachable();
+    let hello = files.first().unreachable();
+    let other = files.last().unreachable();
+    assert_eq!(hello.checksum().map(ToString::to_string), Some(format!("SHA256:{sha256}")));
+
+    let path = std::env::temp_dir().join(format!("httpdirectory-verify-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    httpdir.download(hello, &path).await.unreachable();
+    assert!(hello.verify(&path).await.is_ok());
+
+    let _ = std::fs::remove_file(&path);
+    httpdir.download(other, &path).await.unreachable();
+    match other.verify(&path).await {
+        Ok(()) => panic!("This test should return Err()"),
+        Err(e) => assert!(e.to_string().starts_with(&format!("Checksum of '{}' is ", path.display()))),
+    }
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_refresh_and_diff() {
+    let server = MockServer::start();
+    let url = server.url("/releases/");
+
+    let mut before = server.mock(|when, then| {
+        when.path("/releases/");
+        then.status(200).body(ul_body(&["1.0/", "1.1/"]));
+    });
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    before.delete();
+
+    server.mock(|when, then| {
+        when.path("/releases/");
+        then.status(200).body(ul_body(&["1.1/", "1.2/"]));
+    });
+    let refreshed = httpdir.refresh().await.unreachable();
+    let listing_diff = httpdir.diff(&refreshed);
+
+    assert_eq!(listing_diff.added.iter().filter_map(HttpDirectoryEntry::name).collect::<Vec<_>>(), vec!["1.2/"]);
+    assert_eq!(listing_diff.removed.iter().filter_map(HttpDirectoryEntry::name).collect::<Vec<_>>(), vec!["1.0/"]);
+    assert!(listing_diff.modified.is_empty());
+    assert!(refreshed.diff(&refreshed).is_empty());
+}
+
+#[tokio::test]
+async fn test_watch_conditional_requests() {
+    let server = MockServer::start();
+    let url = server.url("/releases/");
+
+    let mut first = server.mock(|when, then| {
+        when.path("/releases/");
+        then.status(200).body(ul_body(&["1.0/"]));
+    });
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    first.delete();
+
+    let not_modified = server.mock(|when, then| {
+        when.path("/releases/").header("if-none-match", "\"v2\"");
+        then.status(304);
+    });
+    server.mock(|when, then| {
+        when.path("/releases/").header_missing("if-none-match");
+        then.status(200).header("ETag", "\"v2\"").body(ul_body(&["1.0/", "1.1/"]));
+    });
+
+    let mut events = httpdir.watch(Duration::from_millis(10));
+    match events.next().await {
+        Some(Ok(WatchEvent::Added(entry))) => assert_eq!(entry.name(), Some("1.1/")),
+        other => panic!("Unexpected event {other:?}"),
+    }
+    assert!(tokio::time::timeout(Duration::from_millis(100), events.next()).await.is_err());
+    assert!(not_modified.calls() >= 1);
+}
+
+#[tokio::test]
+async fn test_conditional_requests() {
+    let server = MockServer::start();
+    let url = server.url("/releases/");
+
+    let full = server.mock(|when, then| {
+        when.path("/releases/").header_missing("if-none-match");
+        then.status(200).header("ETag", "\"v1\"").body(ul_body(&["1.0/", "1.1/"]));
+    });
+    let not_modified = server.mock(|when, then| {
+        when.path("/releases/").header("if-none-match", "\"v1\"");
+        then.status(304);
+    });
+
+    let builder = HttpDirectoryBuilder::new();
+    let httpdir = builder.build(&url).await.unreachable();
+    let refreshed = httpdir.refresh().await.unreachable();
+    let rebuilt = builder.build(&url).await.unreachable();
+
+    assert_eq!(refreshed.len(), 2);
+    assert_eq!(rebuilt.len(), 2);
+    assert!(httpdir.diff(&refreshed).is_empty());
+    full.assert_calls(1);
+    not_modified.assert_calls(2);
+
+    let builder = HttpDirectoryBuilder::new().conditional_requests(false);
+    let httpdir = builder.build(&url).await.unreachable();
+    assert_eq!(httpdir.refresh().await.unreachable().len(), 2);
+    full.assert_calls(3);
+    not_modified.assert_calls(2);
+}
+
+#[tokio::test]
+async fn test_disk_cache() {
+    let server = MockServer::start();
+    let url = server.url("/releases/");
+    let dir = std::env::temp_dir().join(format!("httpdirectory-integration-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mock = server.mock(|when, then| {
+        when.path("/releases/");
+        then.status(200).body(ul_body(&["1.0/", "1.1/"]));
+    });
+
+    let builder = HttpDirectoryBuilder::new().disk_cache(DiskCache::new(&dir));
+    assert_eq!(builder.build(&url).await.unreachable().len(), 2);
+    assert_eq!(builder.build(&url).await.unreachable().len(), 2);
+    mock.assert_calls(1);
+
+    // Offline mode serves the cache only
+    let offline = HttpDirectoryBuilder::new().disk_cache(DiskCache::new(&dir).ttl(Duration::ZERO).offline(true));
+    let httpdir = offline.build(&url).await.unreachable();
+    assert_eq!(httpdir.len(), 2);
+    mock.assert_calls(1);
+    match httpdir.cd("1.0/").await {
+        Ok(_) => panic!("This test should return Err()"),
+        Err(e) => assert_eq!(e.to_string(), format!("'{url}1.0/' is not in the disk cache (offline mode)")),
+    }
+
+    // An expired listing is requested again
+    let expired = HttpDirectoryBuilder::new().disk_cache(DiskCache::new(&dir).ttl(Duration::ZERO));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(expired.build(&url).await.unreachable().len(), 2);
+    mock.assert_calls(2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_disk_cache_not_modified() {
+    let server = MockServer::start();
+    let url = server.url("/releases/");
+    let dir = std::env::temp_dir().join(format!("httpdirectory-integration-cache-304-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let ok = server.mock(|when, then| {
+        when.path("/releases/").header_missing("if-none-match");
+        then.status(200).header("ETag", "\"v1\"").body(ul_body(&["1.0/", "1.1/"]));
+    });
+    let not_modified = server.mock(|when, then| {
+        when.path("/releases/").header("if-none-match", "\"v1\"");
+        then.status(304);
+    });
+
+    let ttl = Duration::from_millis(300);
+    let httpdir = HttpDirectoryBuilder::new().disk_cache(DiskCache::new(&dir).ttl(ttl)).build(&url).await.unreachable();
+    ok.assert_calls(1);
+
+    // Once expired the listing is requested again and the server tells
+    // that it has not been modified: the cached listing is fresh again
+    tokio::time::sleep(ttl + Duration::from_millis(50)).await;
+    assert_eq!(httpdir.refresh().await.unreachable().len(), 2);
+    not_modified.assert_calls(1);
+
+    assert_eq!(httpdir.refresh().await.unreachable().len(), 2);
+    ok.assert_calls(1);
+    not_modified.assert_calls(1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_download_resume() {
+    let server = MockServer::start();
+    let url = server.url("/pub/");
+
+    server.mock(|when, then| {
+        when.path("/pub/");
+        then.status(200).body(ul_body(&["file.bin"]));
+    });
+    let range = server.mock(|when, then| {
+        when.path("/pub/file.bin").header("range", "bytes=4-").header("accept-encoding", "identity");
+        then.status(206).header("Content-Range", "bytes 4-9/10").body("456789");
+    });
+
+    let httpdir = match HttpDirectory::new(&url, None).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    let path = std::env::temp_dir().join(format!("httpdirectory-integration-{}", std::process::id()));
+    std::fs::write(&path, "0123").unreachable();
+
+    let mut progress = vec![];
+    let downloaded = httpdir.download_with_progress(httpdir.first().unreachable(), &path, |p| progress.push(p)).await;
+
+    assert_eq!(downloaded.ok(), Some(10));
+    assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some("0123456789"));
+    assert_eq!(progress.last().map(|p| (p.downloaded, p.total)), Some((10, Some(10))));
+    range.assert_calls(1);
+    let _ = std::fs::remove_file(&path);
+}
+
+// Understands bodies made of "name,date,size" lines
+#[derive(Debug)]
+struct CsvScraper;
+
+impl Scraper for CsvScraper {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn detect(&self, body: &str) -> Confidence {
+        if body.starts_with("name,date,size") {
+            100
+        } else {
+            0
+        }
+    }
+
+    fn scrape(&self, body: &str) -> httpdirectory::error::Result<Vec<HttpDirectoryEntry>> {
+        Ok(body
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split(',').collect();
+                (columns.len() == 3).then(|| HttpDirectoryEntry::new(columns[0], columns[1], columns[2], columns[0]))
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn test_builder_user_scraper() {
+    let server = MockServer::start();
+    let url = server.url("/csv/");
+
+    let root_mock = server.mock(|when, then| {
+        when.path("/csv/");
+        then.status(200).body("name,date,size\nsub/,2025-01-01 10:00,-\nfile,2025-01-01 10:00,1K");
+    });
+    let dir_mock = server.mock(|when, then| {
+        when.path("/csv/sub/");
+        then.status(200).body("name,date,size\nother,2025-02-01 10:00,12");
+    });
+
+    let httpdir = match HttpDirectoryBuilder::new().scraper(CsvScraper).build(&url).await {
+        Ok(httpdir) => httpdir,
+        Err(e) => panic!("{e}"),
+    };
+    assert_eq!(httpdir.dirs().len(), 1);
+    assert_eq!(httpdir.files().len(), 1);
+
+    // The user scraper is kept by cd()
+    match httpdir.cd("sub/").await {
+        Ok(httpdir) => assert_eq!(httpdir.files().first().and_then(HttpDirectoryEntry::name), Some("other")),
+        Err(e) => panic!("{e}"),
+    }
+
+    root_mock.assert();
+    dir_mock.assert();
+}
+
+#[tokio::test]
+async fn test_nginx_json_and_xml_autoindex() {
+    let server = MockServer::start();
+    let url = server.url("/pub/");
+
+    let json_mock = server.mock(|when, then| {
+        when.path("/pub/");
+        then.status(200).header("Content-Type", "application/json").body(
+            r#"[
+{ "name":"debian", "type":"directory", "mtime":"Wed, 21 Oct 2015 07:28:00 GMT" },
+{ "name":"README", "type":"file", "mtime":"Sat, 05 Apr 2025 11:59:30 GMT", "size":1329 }
+]"#,
+        );
+    });
+    let xml_mock = server.mock(|when, then| {
+        when.path("/pub/debian/");
+        then.status(200).header("Content-Type", "text/xml").body(
+            r#"<?xml version="1.0"?>
+<list>
+<file mtime="2025-04-05T11:59:30Z" size="4836">ftplist</file>
+</list>"#,
+        );
+    });
+
+    let httpdir = HttpDirectory::new(&url, None).await.unreachable();
+    assert_eq!(httpdir.site_type().map(SiteType::name), Some("nginx-json"));
+    assert_eq!(httpdir.dirs().len(), 1);
+    match httpdir.files().first() {
+        Some(HttpDirectoryEntry::File(entry)) => {
+            assert_eq!(entry.exact_size(), Some(1329));
+            assert_eq!(entry.url().map(ToString::to_string), Some(server.url("/pub/README")));
+        }
+        other => panic!("Unexpected entry {other:?}"),
+    }
+
+    let httpdir = httpdir.cd("debian/").await.unreachable();
+    assert_eq!(httpdir.site_type().map(SiteType::name), Some("nginx-xml"));
+    assert_eq!(httpdir.files().first().and_then(HttpDirectoryEntry::name), Some("ftplist"));
+
+    json_mock.assert();
+    xml_mock.assert();
+}
+
 /// Tests <table> tag
 #[tokio::test]
 pub async fn test_debian_example() {
@@ -106,3 +682,27 @@ async fn test_self_miniserve() {
     common::miniserve::run_self_miniserve().unreachable();
     common::miniserve::mock_self_miniserve().await.unreachable();
 }
+
+#[tokio::test]
+async fn test_caddy_browse() {
+    common::caddy::run_caddy_browse().unreachable();
+    common::caddy::mock_caddy_browse().await.unreachable();
+}
+
+#[tokio::test]
+async fn test_lighttpd() {
+    common::lighttpd::run_lighttpd().unreachable();
+    common::lighttpd::mock_lighttpd().await.unreachable();
+}
+
+#[tokio::test]
+async fn test_iis() {
+    common::iis::run_iis().unreachable();
+    common::iis::mock_iis().await.unreachable();
+}
+
+#[tokio::test]
+async fn test_s3_bucket() {
+    common::s3::run_s3_bucket().unreachable();
+    common::s3::mock_s3_bucket().await.unreachable();
+}